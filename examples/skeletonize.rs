@@ -1,4 +1,13 @@
-use skeletonize::edge_detection::{sobel, sobel4};
+use skeletonize::components::{fill_small_holes, remove_small_objects};
+use skeletonize::edge_detection::{
+    canny, color_gradient_magnitude, difference_of_gaussians, kirsch, laplacian_of_gaussian,
+    prewitt, roberts, robinson, scharr, sobel, ColorGradientMethod, GradientKernels,
+};
+use skeletonize::pruning::{prune, PruningMethod};
+#[cfg(feature = "svg")]
+use skeletonize::svg::{write_svg, SvgOptions};
+#[cfg(feature = "svg")]
+use skeletonize::vectorize::{polylines, Simplification};
 use skeletonize::{foreground, thin_image_edges, MarkingMethod};
 use structopt::StructOpt;
 
@@ -13,8 +22,9 @@ fn try_main() -> Result<(), Box<dyn std::error::Error>> {
     let mut opt = Opt::from_args();
 
     // Open image and initialize output filename
-    let img = image::DynamicImage::ImageLuma8(image::open(&opt.input)?.to_luma8());
-    let output = if let Some(output) = opt.output {
+    let input = image::open(&opt.input)?;
+    let img = image::DynamicImage::ImageLuma8(input.to_luma8());
+    let output = if let Some(output) = opt.output.take() {
         output
     } else {
         generate_filename(&opt.input)?.into()
@@ -26,10 +36,44 @@ fn try_main() -> Result<(), Box<dyn std::error::Error>> {
     opt.method.make_ascii_lowercase();
 
     let edge = match opt.edge.as_str() {
-        "sobel" | "s" => EdgeDetection::Sobel,
-        "sobel4" | "s4" => EdgeDetection::Sobel4,
+        // `sobel4` is kept for compatibility and is the same as `sobel`
+        "sobel" | "s" | "sobel4" | "s4" => EdgeDetection::Sobel,
+        "prewitt" | "p" => EdgeDetection::Prewitt,
+        "scharr" | "sc" => EdgeDetection::Scharr,
+        "roberts" | "r" => EdgeDetection::Roberts,
+        "kirsch" | "k" => EdgeDetection::Kirsch,
+        "robinson" | "rb" => EdgeDetection::Robinson,
+        "canny" | "c" => EdgeDetection::Canny,
+        "log" => EdgeDetection::LaplacianOfGaussian,
+        "dog" => EdgeDetection::DifferenceOfGaussians,
         "" => EdgeDetection::None,
-        _ => return Err("Edge detection must be `sobel`/`s` or `sobel4`/`s4`".into()),
+        _ => {
+            return Err(concat!(
+                "Edge detection must be `sobel`/`s`, `sobel4`/`s4`, `prewitt`/`p`, ",
+                "`scharr`/`sc`, `roberts`/`r`, `kirsch`/`k`, `robinson`/`rb`, `canny`/`c`, ",
+                "`log`, or `dog`"
+            )
+            .into())
+        }
+    };
+    // Gradient edge detection can use the color channels instead of luma
+    let edge = if opt.color {
+        let kernels = match edge {
+            EdgeDetection::Sobel => GradientKernels::sobel(),
+            EdgeDetection::Prewitt => GradientKernels::prewitt(),
+            EdgeDetection::Scharr => GradientKernels::scharr(),
+            EdgeDetection::Roberts => GradientKernels::roberts(),
+            _ => {
+                return Err(concat!(
+                    "`--color` requires `sobel`/`s`, `sobel4`/`s4`, `prewitt`/`p`, ",
+                    "`scharr`/`sc`, or `roberts`/`r` edge detection"
+                )
+                .into())
+            }
+        };
+        EdgeDetection::Color(kernels)
+    } else {
+        edge
     };
     let foreground = match opt.foreground.as_str() {
         "black" | "b" => Fg::Black,
@@ -39,28 +83,109 @@ fn try_main() -> Result<(), Box<dyn std::error::Error>> {
     let method = match opt.method.as_str() {
         "modified" | "m" => MarkingMethod::Modified,
         "standard" | "s" => MarkingMethod::Standard,
-        _ => return Err("Method must be `standard`/`s` or `modified`/`m`".into()),
+        "guohall" | "g" => MarkingMethod::GuoHall,
+        _ => return Err("Method must be `standard`/`s`, `modified`/`m`, or `guohall`/`g`".into()),
     };
 
-    // Perform edge detection if one of the Sobel options is passed
+    // Perform edge detection if one of the edge detection options is passed
     let mut filtered = match edge {
         EdgeDetection::Sobel => match foreground {
             Fg::Black => sobel::<foreground::Black>(&img, opt.threshold)?,
             Fg::White => sobel::<foreground::White>(&img, opt.threshold)?,
         },
-        EdgeDetection::Sobel4 => match foreground {
-            Fg::Black => sobel4::<foreground::Black>(&img, opt.threshold)?,
-            Fg::White => sobel4::<foreground::White>(&img, opt.threshold)?,
+        EdgeDetection::Prewitt => match foreground {
+            Fg::Black => prewitt::<foreground::Black>(&img, opt.threshold)?,
+            Fg::White => prewitt::<foreground::White>(&img, opt.threshold)?,
+        },
+        EdgeDetection::Scharr => match foreground {
+            Fg::Black => scharr::<foreground::Black>(&img, opt.threshold)?,
+            Fg::White => scharr::<foreground::White>(&img, opt.threshold)?,
+        },
+        EdgeDetection::Roberts => match foreground {
+            Fg::Black => roberts::<foreground::Black>(&img, opt.threshold)?,
+            Fg::White => roberts::<foreground::White>(&img, opt.threshold)?,
+        },
+        EdgeDetection::Kirsch => match foreground {
+            Fg::Black => kirsch::<foreground::Black>(&img, opt.threshold)?,
+            Fg::White => kirsch::<foreground::White>(&img, opt.threshold)?,
+        },
+        EdgeDetection::Robinson => match foreground {
+            Fg::Black => robinson::<foreground::Black>(&img, opt.threshold)?,
+            Fg::White => robinson::<foreground::White>(&img, opt.threshold)?,
         },
+        EdgeDetection::Canny => {
+            // The low threshold follows edges away from the high threshold
+            let high = opt.threshold.unwrap_or(0.5);
+            let low = 0.4 * high;
+            match foreground {
+                Fg::Black => canny::<foreground::Black>(&img, opt.sigma, low, high)?,
+                Fg::White => canny::<foreground::White>(&img, opt.sigma, low, high)?,
+            }
+        }
+        EdgeDetection::LaplacianOfGaussian => {
+            let t = opt.threshold.unwrap_or(0.05);
+            match foreground {
+                Fg::Black => laplacian_of_gaussian::<foreground::Black>(&img, opt.sigma, t)?,
+                Fg::White => laplacian_of_gaussian::<foreground::White>(&img, opt.sigma, t)?,
+            }
+        }
+        EdgeDetection::DifferenceOfGaussians => {
+            let t = opt.threshold.unwrap_or(0.05);
+            match foreground {
+                Fg::Black => difference_of_gaussians::<foreground::Black>(&img, opt.sigma, t)?,
+                Fg::White => difference_of_gaussians::<foreground::White>(&img, opt.sigma, t)?,
+            }
+        }
+        EdgeDetection::Color(kernels) => {
+            let method = ColorGradientMethod::DiZenzo;
+            match foreground {
+                Fg::Black => color_gradient_magnitude::<foreground::Black>(
+                    &input,
+                    &kernels,
+                    method,
+                    opt.threshold,
+                )?,
+                Fg::White => color_gradient_magnitude::<foreground::White>(
+                    &input,
+                    &kernels,
+                    method,
+                    opt.threshold,
+                )?,
+            }
+        }
         EdgeDetection::None => {
             let mut filtered = img;
             if let Some(t) = opt.threshold {
                 skeletonize::threshold(&mut filtered, t)?;
+            } else if opt.otsu {
+                skeletonize::threshold_otsu(&mut filtered)?;
             }
             filtered
         }
     };
 
+    // Clean up specks of noise which would thin into dots and small loops
+    if let Some(area) = opt.remove_objects {
+        match foreground {
+            Fg::Black => {
+                remove_small_objects::<foreground::Black>(&mut filtered, area)?;
+            }
+            Fg::White => {
+                remove_small_objects::<foreground::White>(&mut filtered, area)?;
+            }
+        }
+    }
+    if let Some(area) = opt.fill_holes {
+        match foreground {
+            Fg::Black => {
+                fill_small_holes::<foreground::Black>(&mut filtered, area)?;
+            }
+            Fg::White => {
+                fill_small_holes::<foreground::White>(&mut filtered, area)?;
+            }
+        }
+    }
+
     // Skip thinning if `no-thin` flag is passed
     if !opt.no_thin {
         match foreground {
@@ -71,14 +196,71 @@ fn try_main() -> Result<(), Box<dyn std::error::Error>> {
                 thin_image_edges::<foreground::White>(&mut filtered, method, None)?;
             }
         }
+
+        // Remove short spurs left by noise along the edges of lines
+        if let Some(length) = opt.prune {
+            match foreground {
+                Fg::Black => {
+                    prune::<foreground::Black>(&mut filtered, PruningMethod::Branches, length)?;
+                }
+                Fg::White => {
+                    prune::<foreground::White>(&mut filtered, PruningMethod::Branches, length)?;
+                }
+            }
+        }
     }
 
+    #[cfg(feature = "svg")]
+    save_svg(&opt, &foreground, &filtered)?;
+
     Ok(filtered.save(output)?)
 }
 
+/// Trace the lines of the image and save them as an SVG image if `--svg` is
+/// passed.
+#[cfg(feature = "svg")]
+fn save_svg(
+    opt: &Opt,
+    foreground: &Fg,
+    img: &image::DynamicImage,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = match &opt.svg {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    let method = Simplification::DouglasPeucker;
+    let lines = match foreground {
+        Fg::Black => polylines::<foreground::Black>(img, method, opt.simplify)?,
+        Fg::White => polylines::<foreground::White>(img, method, opt.simplify)?,
+    };
+    let options = SvgOptions {
+        stroke_width: opt.stroke_width,
+        curves: opt.curves,
+        ..SvgOptions::default()
+    };
+
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    Ok(write_svg(
+        file,
+        &lines,
+        img.width(),
+        img.height(),
+        &options,
+    )?)
+}
+
 enum EdgeDetection {
     Sobel,
-    Sobel4,
+    Prewitt,
+    Scharr,
+    Roberts,
+    Kirsch,
+    Robinson,
+    Canny,
+    LaplacianOfGaussian,
+    DifferenceOfGaussians,
+    Color(GradientKernels),
     None,
 }
 
@@ -102,7 +284,8 @@ pub struct Opt {
     #[structopt(short, long, default_value = "black")]
     pub foreground: String,
 
-    /// Edge thinning algorithm to use, `standard`/`s` or `modified`/`m`.
+    /// Edge thinning algorithm to use, `standard`/`s`, `modified`/`m`, or
+    /// `guohall`/`g`.
     #[structopt(short, long, default_value = "modified")]
     pub method: String,
 
@@ -114,11 +297,67 @@ pub struct Opt {
     #[structopt(short, long)]
     pub threshold: Option<f32>,
 
-    /// Run a Sobel edge detection filter on the image before image thinning.
-    /// `sobel`/`s` or `sobel4`/`s4` are available options.
+    /// Threshold the image at a level chosen automatically with Otsu's method
+    /// when `--threshold` isn't passed. Not used with edge detection.
+    #[structopt(long)]
+    pub otsu: bool,
+
+    /// Run an edge detection filter on the image before image thinning.
+    /// `sobel`/`s`, `sobel4`/`s4`, `prewitt`/`p`, `scharr`/`sc`, `roberts`/`r`,
+    /// `kirsch`/`k`, `robinson`/`rb`, `canny`/`c`, `log`, or `dog` are
+    /// available options.
+    ///
+    /// For `canny`, `--threshold` is the high threshold and defaults to 0.5.
+    /// For `log` and `dog`, `--threshold` is the smallest change across a zero
+    /// crossing and defaults to 0.05.
     #[structopt(short, long, default_value = "")]
     pub edge: String,
 
+    /// Detect edges on the red, green, and blue channels so that edges between
+    /// colors of the same brightness are kept. Only used with `sobel`,
+    /// `sobel4`, `prewitt`, `scharr`, or `roberts` edge detection.
+    #[structopt(long)]
+    pub color: bool,
+
+    /// Standard deviation of the Gaussian smoothing used by `canny`, `log`,
+    /// and `dog` edge detection.
+    #[structopt(long, default_value = "1.0")]
+    pub sigma: f32,
+
+    /// Remove objects with fewer than this many pixels before thinning.
+    #[structopt(long)]
+    pub remove_objects: Option<u64>,
+
+    /// Fill holes in objects with at most this many pixels before thinning.
+    #[structopt(long)]
+    pub fill_holes: Option<u64>,
+
+    /// Remove branches shorter than this many pixels which end in an endpoint
+    /// after thinning.
+    #[structopt(long)]
+    pub prune: Option<u32>,
+
+    /// Trace the lines of the output image and save them to this SVG file.
+    #[cfg(feature = "svg")]
+    #[structopt(long, parse(from_os_str))]
+    pub svg: Option<std::path::PathBuf>,
+
+    /// Largest distance in pixels that a traced line can move from the
+    /// skeleton when simplifying the lines of `--svg` output.
+    #[cfg(feature = "svg")]
+    #[structopt(long, default_value = "0.5")]
+    pub simplify: f32,
+
+    /// Width of the lines in `--svg` output.
+    #[cfg(feature = "svg")]
+    #[structopt(long, default_value = "1.0")]
+    pub stroke_width: f32,
+
+    /// Draw the lines of `--svg` output as smooth curves.
+    #[cfg(feature = "svg")]
+    #[structopt(long)]
+    pub curves: bool,
+
     /// Disables the edge thinning pass, used for generating images with only
     /// thresholding or edge detection performed.
     #[structopt(long)]
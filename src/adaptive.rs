@@ -0,0 +1,194 @@
+//! Adaptive thresholding for binarizing unevenly lit images.
+//!
+//! The global [`threshold`](crate::threshold) function uses the same level for
+//! every pixel. The functions in this module compare each pixel against a
+//! level calculated from the surrounding window instead, so that shadows and
+//! gradients in the lighting don't merge into the foreground.
+//!
+//! Local means and variances are calculated with integral images, so the cost
+//! of thresholding doesn't depend on the window size.
+
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::ForegroundColor;
+
+/// The method used to calculate the threshold of each pixel from its window.
+///
+/// Luma values are scaled to range from 0.0 to 1.0, where `m` is the mean and
+/// `s` is the standard deviation of the window.
+///
+/// ### Reference
+///
+/// <span id="niblack"></span>Niblack, Wayne. (1986). An Introduction to Digital
+/// Image Processing. Prentice-Hall. 115-116.
+///
+/// <span id="sauvola"></span>Sauvola, J. & Pietikäinen, M. (2000). Adaptive
+/// document image binarization. Pattern Recognition. 33. 225-236.
+/// [DOI:10.1016/S0031-3203(99)00055-2](https://doi.org/10.1016/S0031-3203(99)00055-2)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AdaptiveMethod {
+    /// Threshold at `m - k`, where `m` is the mean of a square window.
+    Mean,
+    /// Threshold at `m - k`, where `m` is a Gaussian weighted mean which
+    /// favors pixels near the center of the window. The Gaussian is
+    /// approximated with three passes of a box filter. Windows smaller than 7
+    /// use boxes of width 3, which spread slightly past the window.
+    Gaussian,
+    /// Threshold at `m + k * s`. `k` is usually negative, around `-0.2`, for
+    /// dark text on a light background.
+    ///
+    /// See [AdaptiveMethod](crate::adaptive::AdaptiveMethod#niblack) for
+    /// reference.
+    Niblack,
+    /// Threshold at `m * (1 + k * (s / 0.5 - 1))`. `k` is usually between
+    /// `0.2` and `0.5`. Unlike `Niblack`, the threshold drops in flat regions
+    /// of the background which reduces noise.
+    ///
+    /// See [AdaptiveMethod](crate::adaptive::AdaptiveMethod#sauvola) for
+    /// reference.
+    Sauvola,
+}
+
+/// Create a binary image where pixels darker than the threshold calculated
+/// from their surrounding window become the foreground color of `F` and all
+/// other pixels become the background color. The image must be grayscale.
+///
+/// `window` is the width and height of the square window centered on each
+/// pixel, even values are rounded up to the next odd number. The window is
+/// clipped at the borders of the image. `k` is the parameter of the
+/// [`AdaptiveMethod`](crate::adaptive::AdaptiveMethod).
+///
+/// If `invert` is `true`, pixels lighter than their threshold become the
+/// foreground color instead, such as when the lines in the image are lighter
+/// than their surroundings. The luma is inverted before the thresholds are
+/// calculated, so `k` has the same meaning for both.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::adaptive::{adaptive_threshold, AdaptiveMethod};
+/// use skeletonize::foreground;
+///
+/// // A dark line across a page that fades from white to gray
+/// let luma = image::GrayImage::from_fn(64, 16, |x, y| {
+///     let page = 250 - 2 * x as u8;
+///     image::Luma([if y == 8 { page - 60 } else { page }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let method = AdaptiveMethod::Sauvola;
+/// let binary = adaptive_threshold::<foreground::Black>(&img, method, 7, 0.2, false)?;
+/// for (_, y, p) in binary.as_luma8().unwrap().enumerate_pixels() {
+///     assert_eq!(p[0], if y == 8 { 0 } else { 255 });
+/// }
+///
+/// // A light line on a dark background, thresholded to a white foreground
+/// let mut img = img;
+/// img.invert();
+/// let binary = adaptive_threshold::<foreground::White>(&img, method, 7, 0.2, true)?;
+/// for (_, y, p) in binary.as_luma8().unwrap().enumerate_pixels() {
+///     assert_eq!(p[0], if y == 8 { 255 } else { 0 });
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn adaptive_threshold<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    method: AdaptiveMethod,
+    window: u32,
+    k: f32,
+    invert: bool,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    let luma_img = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::AdaptiveThresholdLuma,
+    ))?;
+    let (width, height) = luma_img.dimensions();
+    let radius = window / 2;
+    let k = f64::from(k);
+
+    let luma: Vec<f64> = luma_img
+        .iter()
+        .map(|&p| f64::from(if invert { !p } else { p }) / 255.0)
+        .collect();
+
+    let thresholds = match method {
+        AdaptiveMethod::Mean => {
+            let mut means = box_mean(&luma, width, height, radius);
+            means.iter_mut().for_each(|m| *m -= k);
+            means
+        }
+        AdaptiveMethod::Gaussian => {
+            // Three box filters with a third of the window each approximate a
+            // Gaussian spanning the window, smaller windows still need a box
+            // larger than a single pixel
+            let box_radius = (radius / 3).max(1);
+            let mut means = box_mean(&luma, width, height, box_radius);
+            for _ in 0..2 {
+                means = box_mean(&means, width, height, box_radius);
+            }
+            means.iter_mut().for_each(|m| *m -= k);
+            means
+        }
+        AdaptiveMethod::Niblack | AdaptiveMethod::Sauvola => {
+            let squares: Vec<f64> = luma.iter().map(|&p| p * p).collect();
+            let means = box_mean(&luma, width, height, radius);
+            let square_means = box_mean(&squares, width, height, radius);
+
+            means
+                .into_iter()
+                .zip(square_means)
+                .map(|(m, sq)| {
+                    let s = (sq - m * m).max(0.0).sqrt();
+                    if method == AdaptiveMethod::Niblack {
+                        m + k * s
+                    } else {
+                        m * (1.0 + k * (s / 0.5 - 1.0))
+                    }
+                })
+                .collect()
+        }
+    };
+
+    let binary = image::GrayImage::from_fn(width, height, |x, y| {
+        let index = y as usize * width as usize + x as usize;
+        if luma[index] < thresholds[index] {
+            image::Luma([!F::BACKGROUND_COLOR])
+        } else {
+            image::Luma([F::BACKGROUND_COLOR])
+        }
+    });
+
+    Ok(image::DynamicImage::ImageLuma8(binary))
+}
+
+/// Calculate the mean of the square window of `radius` around every value of
+/// a `width` by `height` buffer using an integral image.
+fn box_mean(values: &[f64], width: u32, height: u32, radius: u32) -> Vec<f64> {
+    let (width, height, radius) = (width as usize, height as usize, radius as usize);
+
+    // The integral image has an extra row and column of zeros at the start
+    let stride = width + 1;
+    let mut integral = vec![0.0; stride * (height + 1)];
+    for y in 0..height {
+        let mut row_sum = 0.0;
+        for x in 0..width {
+            row_sum += values[y * width + x];
+            integral[(y + 1) * stride + x + 1] = integral[y * stride + x + 1] + row_sum;
+        }
+    }
+
+    let mut means = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let top = y.saturating_sub(radius);
+        let bottom = (y + radius + 1).min(height);
+        for x in 0..width {
+            let left = x.saturating_sub(radius);
+            let right = (x + radius + 1).min(width);
+
+            let sum = integral[bottom * stride + right] - integral[top * stride + right]
+                + integral[top * stride + left]
+                - integral[bottom * stride + left];
+            means.push(sum / ((bottom - top) * (right - left)) as f64);
+        }
+    }
+
+    means
+}
@@ -0,0 +1,225 @@
+//! Bit-packed storage for binary images.
+
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::ForegroundColor;
+
+/// Number of pixels stored in each word of a [`BinaryImage`] row.
+const WORD_BITS: u32 = u64::BITS;
+
+/// A binary image storing one bit per pixel, where a set bit is a foreground
+/// pixel.
+///
+/// Rows are packed into `u64` words starting from the least significant bit,
+/// and each row begins on a new word. A `BinaryImage` uses an eighth of the
+/// memory of a `GrayImage` and its neighborhoods can be read a row of words at
+/// a time.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::{foreground, thin_binary_image_edges, thin_image_edges};
+/// use skeletonize::{BinaryImage, MarkingMethod};
+///
+/// let luma = image::GrayImage::from_fn(12, 8, |x, y| {
+///     image::Luma([if (2..10).contains(&x) && (2..6).contains(&y) { 0 } else { 255 }])
+/// });
+/// let mut img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let mut binary = BinaryImage::from_dynamic::<foreground::Black>(&img)?;
+/// assert_eq!(binary.count_foreground(), 32);
+///
+/// thin_binary_image_edges(&mut binary, MarkingMethod::Modified, None)?;
+/// thin_image_edges::<foreground::Black>(&mut img, MarkingMethod::Modified, None)?;
+/// assert_eq!(binary.to_dynamic::<foreground::Black>(), img);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryImage {
+    width: u32,
+    height: u32,
+    words_per_row: usize,
+    data: Vec<u64>,
+}
+
+impl BinaryImage {
+    /// Create a new `BinaryImage` of the given dimensions with every pixel set
+    /// to the background.
+    pub fn new(width: u32, height: u32) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS) as usize;
+
+        Self {
+            width,
+            height,
+            words_per_row,
+            data: vec![0; words_per_row * height as usize],
+        }
+    }
+
+    /// Create a `BinaryImage` from `img` where every pixel which isn't the
+    /// background color of `F` becomes foreground.
+    pub fn from_luma<F: ForegroundColor>(img: &image::GrayImage) -> Self {
+        let (width, height) = img.dimensions();
+        let mut binary = Self::new(width, height);
+
+        for (x, y, p) in img.enumerate_pixels() {
+            if p[0] != F::BACKGROUND_COLOR {
+                binary.set(x, y, true);
+            }
+        }
+
+        binary
+    }
+
+    /// Create a `BinaryImage` from a grayscale `img` where every pixel which
+    /// isn't the background color of `F` becomes foreground.
+    pub fn from_dynamic<F: ForegroundColor>(
+        img: &image::DynamicImage,
+    ) -> Result<Self, SkeletonizeError> {
+        let luma_img = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+            LumaConversionErrorKind::BinaryImageLuma,
+        ))?;
+
+        Ok(Self::from_luma::<F>(luma_img))
+    }
+
+    /// Convert the image into a `GrayImage` using the foreground and background
+    /// colors of `F`.
+    pub fn to_luma<F: ForegroundColor>(&self) -> image::GrayImage {
+        image::GrayImage::from_fn(self.width, self.height, |x, y| {
+            if self.get(x, y) {
+                image::Luma([!F::BACKGROUND_COLOR])
+            } else {
+                image::Luma([F::BACKGROUND_COLOR])
+            }
+        })
+    }
+
+    /// Convert the image into a grayscale `DynamicImage` using the foreground
+    /// and background colors of `F`.
+    pub fn to_dynamic<F: ForegroundColor>(&self) -> image::DynamicImage {
+        image::DynamicImage::ImageLuma8(self.to_luma::<F>())
+    }
+
+    /// The width of the image.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The width and height of the image.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Return `true` if the pixel at `(x, y)` is foreground.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is outside of the image.
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        let (index, bit) = self.position(x, y);
+        self.data[index] >> bit & 1 != 0
+    }
+
+    /// Set the pixel at `(x, y)` to foreground if `value` is `true`, otherwise
+    /// set it to background.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is outside of the image.
+    pub fn set(&mut self, x: u32, y: u32, value: bool) {
+        let (index, bit) = self.position(x, y);
+        if value {
+            self.data[index] |= 1 << bit;
+        } else {
+            self.data[index] &= !(1 << bit);
+        }
+    }
+
+    /// The number of foreground pixels in the image.
+    pub fn count_foreground(&self) -> u64 {
+        self.data
+            .iter()
+            .map(|word| u64::from(word.count_ones()))
+            .sum()
+    }
+
+    /// The packed words of row `y`. Bits past the width of the image are
+    /// always unset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is outside of the image.
+    pub fn row(&self, y: u32) -> &[u64] {
+        assert!(y < self.height, "row {} is outside of the image", y);
+        let start = y as usize * self.words_per_row;
+
+        &self.data[start..start + self.words_per_row]
+    }
+
+    /// Calculate and return the status of the neighboring pixels packed into
+    /// the bits of a `u8`, in the same order as
+    /// [`get_neighbor_bits`](crate::neighbors::get_neighbor_bits).
+    ///
+    /// Returns `None` for pixels on the border of the image which don't have 8
+    /// neighbors, and for coordinates outside of the image.
+    pub fn neighbor_bits(&self, x: u32, y: u32) -> Option<u8> {
+        if x == 0
+            || y == 0
+            || x >= self.width.saturating_sub(1)
+            || y >= self.height.saturating_sub(1)
+        {
+            return None;
+        }
+
+        // Bits 0, 1, and 2 hold the pixels at x - 1, x, and x + 1
+        let above = self.row_triple(y - 1, x - 1);
+        let middle = self.row_triple(y, x - 1);
+        let below = self.row_triple(y + 1, x - 1);
+
+        Some(
+            (above >> 1 & 1)
+                | (above >> 2 & 1) << 1
+                | (middle >> 2 & 1) << 2
+                | (below >> 2 & 1) << 3
+                | (below >> 1 & 1) << 4
+                | (below & 1) << 5
+                | (middle & 1) << 6
+                | (above & 1) << 7,
+        )
+    }
+
+    /// Read the three pixels starting at `x` in row `y` into the low bits of a
+    /// `u8`, which may straddle two words.
+    fn row_triple(&self, y: u32, x: u32) -> u8 {
+        let row = self.row(y);
+        let index = (x / WORD_BITS) as usize;
+        let bit = x % WORD_BITS;
+
+        let mut bits = row[index] >> bit;
+        if bit > WORD_BITS - 3 {
+            bits |= row[index + 1] << (WORD_BITS - bit);
+        }
+
+        (bits & 0b111) as u8
+    }
+
+    /// Word index and bit offset of the pixel at `(x, y)`.
+    fn position(&self, x: u32, y: u32) -> (usize, u32) {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({}, {}) is outside of the image",
+            x,
+            y
+        );
+
+        (
+            y as usize * self.words_per_row + (x / WORD_BITS) as usize,
+            x % WORD_BITS,
+        )
+    }
+}
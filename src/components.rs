@@ -0,0 +1,349 @@
+//! Connected component labeling for binary images.
+//!
+//! Each group of connected foreground pixels is a component, such as a stroke
+//! of a letter or a separate object in a scan. Labeling finds which component
+//! every pixel belongs to and measures the size and position of each
+//! component, which can be used to count objects, filter them by size, or thin
+//! them one at a time.
+//!
+//! [`remove_small_objects`](remove_small_objects) and
+//! [`fill_small_holes`](fill_small_holes) clean up specks of noise in
+//! thresholded images before thinning.
+
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::neighbors::RING_OFFSETS;
+use crate::{BinaryImage, ForegroundColor};
+
+/// Which neighbors of a pixel are connected to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Pixels are connected to the 4 neighbors which share an edge with them.
+    Four,
+    /// Pixels are connected to all 8 neighbors, including diagonal neighbors.
+    /// The lines of a thinned image are 8-connected.
+    #[default]
+    Eight,
+}
+
+/// The smallest rectangle containing every pixel of a component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoundingBox {
+    /// Horizontal position of the left column.
+    pub x: u32,
+    /// Vertical position of the top row.
+    pub y: u32,
+    /// Number of columns in the rectangle.
+    pub width: u32,
+    /// Number of rows in the rectangle.
+    pub height: u32,
+}
+
+/// Measurements of a connected component.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Component {
+    /// Label of the component's pixels in [`Labeling::labels`](Labeling::labels).
+    pub label: u32,
+    /// Number of pixels in the component.
+    pub area: u64,
+    /// The smallest rectangle containing the component.
+    pub bounding_box: BoundingBox,
+    /// Average position of the pixels in the component.
+    pub centroid: (f32, f32),
+}
+
+/// The connected components of an image, created by
+/// [`label_components`](label_components).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Labeling {
+    /// Label of each pixel, which is `0` for the background. Components are
+    /// labeled from `1` in the order of their first pixel in row-major order.
+    pub labels: image::ImageBuffer<image::Luma<u32>, Vec<u32>>,
+    /// Components ordered by label, so the component labeled `n` is at index
+    /// `n - 1`.
+    pub components: Vec<Component>,
+}
+
+impl Labeling {
+    /// Create a [`BinaryImage`](crate::BinaryImage) where only the pixels of
+    /// the component labeled `label` are foreground, for processing one
+    /// component at a time.
+    pub fn mask(&self, label: u32) -> BinaryImage {
+        let (width, height) = self.labels.dimensions();
+        let mut mask = BinaryImage::new(width, height);
+
+        for (x, y, p) in self.labels.enumerate_pixels() {
+            if p[0] == label && label != 0 {
+                mask.set(x, y, true);
+            }
+        }
+
+        mask
+    }
+}
+
+/// Label the connected components of a binary image, where every pixel which
+/// isn't the background color of `F` is foreground.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::components::{label_components, BoundingBox, Connectivity};
+/// use skeletonize::foreground;
+///
+/// // Two squares which touch at a corner, and a bar
+/// let luma = image::GrayImage::from_fn(12, 10, |x, y| {
+///     let first = (1..4).contains(&x) && (1..4).contains(&y);
+///     let second = (4..6).contains(&x) && (4..6).contains(&y);
+///     let bar = (1..11).contains(&x) && y == 8;
+///     image::Luma([if first || second || bar { 0 } else { 255 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let eight = label_components::<foreground::Black>(&img, Connectivity::Eight)?;
+/// assert_eq!(eight.components.len(), 2);
+/// assert_eq!(eight.labels[(5, 5)][0], eight.labels[(1, 1)][0]);
+/// assert_eq!(eight.components[0].area, 13);
+///
+/// let four = label_components::<foreground::Black>(&img, Connectivity::Four)?;
+/// assert_eq!(four.components.len(), 3);
+///
+/// let bar = &four.components[2];
+/// assert_eq!(bar.area, 10);
+/// assert_eq!(bar.centroid, (5.5, 8.0));
+/// assert_eq!(
+///     bar.bounding_box,
+///     BoundingBox { x: 1, y: 8, width: 10, height: 1 }
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn label_components<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    connectivity: Connectivity,
+) -> Result<Labeling, SkeletonizeError> {
+    let luma = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::ComponentLabelingLuma,
+    ))?;
+    let (width, height) = luma.dimensions();
+    let foreground: Vec<bool> = luma.iter().map(|&p| p != F::BACKGROUND_COLOR).collect();
+
+    Ok(label(&foreground, width, height, connectivity))
+}
+
+/// Label the connected components of a
+/// [`BinaryImage`](crate::BinaryImage). See
+/// [`label_components`](label_components) for details.
+pub fn label_binary_image(img: &BinaryImage, connectivity: Connectivity) -> Labeling {
+    let (width, height) = img.dimensions();
+    let foreground: Vec<bool> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| img.get(x, y)))
+        .collect();
+
+    label(&foreground, width, height, connectivity)
+}
+
+/// Label the connected `true` pixels of a `width` by `height` buffer by
+/// flooding each component from its first pixel.
+pub(crate) fn label(
+    foreground: &[bool],
+    width: u32,
+    height: u32,
+    connectivity: Connectivity,
+) -> Labeling {
+    let offsets: &[(i64, i64)] = match connectivity {
+        Connectivity::Four => &[(0, -1), (1, 0), (0, 1), (-1, 0)],
+        Connectivity::Eight => &RING_OFFSETS,
+    };
+    let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+
+    let mut labels = vec![0; foreground.len()];
+    let mut components = Vec::new();
+    let mut stack = Vec::new();
+
+    for (x, y) in (0..height).flat_map(|y| (0..width).map(move |x| (x, y))) {
+        if !foreground[index(x, y)] || labels[index(x, y)] != 0 {
+            continue;
+        }
+
+        let label = components.len() as u32 + 1;
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (x, y, x, y);
+        let (mut area, mut sum_x, mut sum_y) = (0, 0, 0);
+
+        labels[index(x, y)] = label;
+        stack.push((x, y));
+        while let Some((px, py)) = stack.pop() {
+            area += 1;
+            sum_x += u64::from(px);
+            sum_y += u64::from(py);
+            min_x = min_x.min(px);
+            min_y = min_y.min(py);
+            max_x = max_x.max(px);
+            max_y = max_y.max(py);
+
+            for &(dx, dy) in offsets {
+                let (nx, ny) = (i64::from(px) + dx, i64::from(py) + dy);
+                if nx < 0 || ny < 0 || nx >= i64::from(width) || ny >= i64::from(height) {
+                    continue;
+                }
+
+                let i = index(nx as u32, ny as u32);
+                if foreground[i] && labels[i] == 0 {
+                    labels[i] = label;
+                    stack.push((nx as u32, ny as u32));
+                }
+            }
+        }
+
+        components.push(Component {
+            label,
+            area,
+            bounding_box: BoundingBox {
+                x: min_x,
+                y: min_y,
+                width: max_x - min_x + 1,
+                height: max_y - min_y + 1,
+            },
+            centroid: (
+                (sum_x as f64 / area as f64) as f32,
+                (sum_y as f64 / area as f64) as f32,
+            ),
+        });
+    }
+
+    Labeling {
+        labels: image::ImageBuffer::from_raw(width, height, labels)
+            .expect("buffer matches image dimensions"),
+        components,
+    }
+}
+
+/// Remove the 8-connected objects which have fewer than `min_area` pixels from
+/// a binary image by setting them to the background color of `F`. Returns the
+/// number of pixels that were removed.
+///
+/// Removing specks of noise before thinning keeps them from becoming small
+/// dots and loops in the skeleton.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::components::remove_small_objects;
+/// use skeletonize::foreground;
+///
+/// // A black square with two specks of noise
+/// let luma = image::GrayImage::from_fn(12, 12, |x, y| {
+///     let square = (2..8).contains(&x) && (2..8).contains(&y);
+///     let specks = (x, y) == (10, 1) || ((9..11).contains(&x) && y == 10);
+///     image::Luma([if square || specks { 0 } else { 255 }])
+/// });
+/// let mut img = image::DynamicImage::ImageLuma8(luma);
+///
+/// assert_eq!(remove_small_objects::<foreground::Black>(&mut img, 3)?, 3);
+///
+/// let cleaned = img.as_luma8().unwrap();
+/// assert_eq!(cleaned[(10, 1)][0], 255);
+/// assert_eq!(cleaned[(9, 10)][0], 255);
+/// assert_eq!(cleaned[(4, 4)][0], 0);
+/// # Ok(())
+/// # }
+/// ```
+pub fn remove_small_objects<F: ForegroundColor>(
+    img: &mut image::DynamicImage,
+    min_area: u64,
+) -> Result<u64, SkeletonizeError> {
+    let luma = img.as_mut_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::SmallComponentsMutableLuma,
+    ))?;
+    let (width, height) = luma.dimensions();
+    let foreground: Vec<bool> = luma.iter().map(|&p| p != F::BACKGROUND_COLOR).collect();
+
+    let labeling = label(&foreground, width, height, Connectivity::Eight);
+    let small: Vec<bool> = labeling
+        .components
+        .iter()
+        .map(|component| component.area < min_area)
+        .collect();
+
+    Ok(replace_labels(luma, &labeling, &small, F::BACKGROUND_COLOR))
+}
+
+/// Fill the holes which have at most `max_area` pixels in the objects of a
+/// binary image by setting them to the foreground color of `F`. Returns the
+/// number of pixels that were filled.
+///
+/// Holes are 4-connected groups of background pixels which don't touch the
+/// border of the image, so background between diagonal pixels of an
+/// 8-connected object is part of the hole. Filling small holes before thinning
+/// keeps them from becoming small loops in the skeleton.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::components::fill_small_holes;
+/// use skeletonize::foreground;
+///
+/// // A white square with a 1 pixel hole and a 9 pixel hole
+/// let luma = image::GrayImage::from_fn(16, 10, |x, y| {
+///     let square = (1..15).contains(&x) && (1..9).contains(&y);
+///     let small = (x, y) == (3, 4);
+///     let large = (8..11).contains(&x) && (3..6).contains(&y);
+///     image::Luma([if square && !small && !large { 255 } else { 0 }])
+/// });
+/// let mut img = image::DynamicImage::ImageLuma8(luma);
+///
+/// assert_eq!(fill_small_holes::<foreground::White>(&mut img, 4)?, 1);
+///
+/// let filled = img.as_luma8().unwrap();
+/// assert_eq!(filled[(3, 4)][0], 255);
+/// assert_eq!(filled[(9, 4)][0], 0);
+/// assert_eq!(filled[(0, 0)][0], 0);
+/// # Ok(())
+/// # }
+/// ```
+pub fn fill_small_holes<F: ForegroundColor>(
+    img: &mut image::DynamicImage,
+    max_area: u64,
+) -> Result<u64, SkeletonizeError> {
+    let luma = img.as_mut_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::SmallComponentsMutableLuma,
+    ))?;
+    let (width, height) = luma.dimensions();
+    let background: Vec<bool> = luma.iter().map(|&p| p == F::BACKGROUND_COLOR).collect();
+
+    let labeling = label(&background, width, height, Connectivity::Four);
+    let small: Vec<bool> = labeling
+        .components
+        .iter()
+        .map(|component| {
+            let bounds = component.bounding_box;
+            let border = bounds.x == 0
+                || bounds.y == 0
+                || bounds.x + bounds.width == width
+                || bounds.y + bounds.height == height;
+            !border && component.area <= max_area
+        })
+        .collect();
+
+    Ok(replace_labels(
+        luma,
+        &labeling,
+        &small,
+        !F::BACKGROUND_COLOR,
+    ))
+}
+
+/// Set the pixels of the components marked in `selected` to `value`, and
+/// return the number of pixels which were set.
+fn replace_labels(
+    luma: &mut image::GrayImage,
+    labeling: &Labeling,
+    selected: &[bool],
+    value: u8,
+) -> u64 {
+    let mut replaced = 0;
+    for (p, &label) in luma.iter_mut().zip(labeling.labels.iter()) {
+        if label != 0 && selected[label as usize - 1] {
+            *p = value;
+            replaced += 1;
+        }
+    }
+
+    replaced
+}
@@ -0,0 +1,260 @@
+//! Distance transforms of binary images.
+//!
+//! A distance transform replaces every foreground pixel with its distance to
+//! the nearest background pixel, which is the basis for measuring the width of
+//! shapes and for the [`medial_axis`](crate::medial_axis) transform. Pixels
+//! outside of the image aren't background, and every foreground pixel is
+//! infinitely far away if the image doesn't have any background pixels.
+
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::ForegroundColor;
+
+/// The metric used to measure the distance between two pixels.
+///
+/// ### Reference
+///
+/// <span id="borgefors"></span>Borgefors, Gunilla. (1986). Distance
+/// transformations in digital images. Computer Vision, Graphics, and Image
+/// Processing. 34. 344-371.
+/// [DOI:10.1016/S0734-189X(86)80047-0](https://doi.org/10.1016/S0734-189X(86)80047-0)
+///
+/// <span id="felzenszwalb"></span>Felzenszwalb, Pedro F. & Huttenlocher,
+/// Daniel P. (2012). Distance Transforms of Sampled Functions. Theory of
+/// Computing. 8. 415-428.
+/// [DOI:10.4086/toc.2012.v008a019](https://doi.org/10.4086/toc.2012.v008a019)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DistanceMetric {
+    /// The exact straight line distance.
+    ///
+    /// See [DistanceMetric](crate::distance::DistanceMetric#felzenszwalb) for
+    /// reference.
+    Euclidean,
+    /// An approximation of the Euclidean distance where orthogonal steps cost
+    /// 3 and diagonal steps cost 4, divided by 3 so that distances are
+    /// measured in pixels.
+    ///
+    /// See [DistanceMetric](crate::distance::DistanceMetric#borgefors) for
+    /// reference.
+    Chamfer,
+    /// The number of orthogonal steps between pixels, also known as the
+    /// Manhattan or taxicab distance.
+    CityBlock,
+    /// The number of orthogonal or diagonal steps between pixels, also known as
+    /// the Chebyshev distance.
+    Chessboard,
+}
+
+/// Calculate the distance from every pixel of a binary image to the nearest
+/// background pixel, where every pixel which isn't the background color of `F`
+/// is foreground. Background pixels have a distance of 0.0.
+///
+/// Distances are measured in pixels. [`to_image`](to_image) converts the
+/// distances into an image which can be saved.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::distance::{distance_transform, DistanceMetric};
+/// use skeletonize::foreground;
+///
+/// // A single background pixel in the middle of a black image
+/// let mut luma = image::GrayImage::new(7, 7);
+/// luma.put_pixel(3, 3, image::Luma([255]));
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let corner = |metric| -> Result<f32, skeletonize::error::SkeletonizeError> {
+///     Ok(distance_transform::<foreground::Black>(&img, metric)?[(0, 0)][0])
+/// };
+/// assert_eq!(corner(DistanceMetric::Euclidean)?, 18f32.sqrt());
+/// assert_eq!(corner(DistanceMetric::Chamfer)?, 4.0);
+/// assert_eq!(corner(DistanceMetric::CityBlock)?, 6.0);
+/// assert_eq!(corner(DistanceMetric::Chessboard)?, 3.0);
+///
+/// let distances = distance_transform::<foreground::Black>(&img, DistanceMetric::Euclidean)?;
+/// assert_eq!(distances[(3, 3)][0], 0.0);
+/// assert_eq!(distances[(3, 5)][0], 2.0);
+/// # Ok(())
+/// # }
+/// ```
+pub fn distance_transform<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    metric: DistanceMetric,
+) -> Result<image::ImageBuffer<image::Luma<f32>, Vec<f32>>, SkeletonizeError> {
+    let luma = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::DistanceTransformLuma,
+    ))?;
+    let (width, height) = luma.dimensions();
+    let foreground: Vec<bool> = luma.iter().map(|&p| p != F::BACKGROUND_COLOR).collect();
+
+    let distances: Vec<f32> = match metric {
+        DistanceMetric::Euclidean => squared_euclidean(&foreground, width, height)
+            .into_iter()
+            .map(|d| d.sqrt() as f32)
+            .collect(),
+        DistanceMetric::Chamfer => chamfer(&foreground, width, height, 3, 4),
+        DistanceMetric::CityBlock => chamfer(&foreground, width, height, 1, u32::MAX),
+        DistanceMetric::Chessboard => chamfer(&foreground, width, height, 1, 1),
+    };
+
+    Ok(image::ImageBuffer::from_raw(width, height, distances)
+        .expect("buffer matches image dimensions"))
+}
+
+/// Convert distances into a 16-bit grayscale image where the largest distance
+/// is white and background pixels are black. Infinite distances are also
+/// white.
+pub fn to_image(distances: &image::ImageBuffer<image::Luma<f32>, Vec<f32>>) -> image::DynamicImage {
+    let max = distances
+        .iter()
+        .copied()
+        .filter(|d| d.is_finite())
+        .fold(0.0, f32::max);
+    let scale = if max > 0.0 {
+        f32::from(u16::MAX) / max
+    } else {
+        0.0
+    };
+
+    let (width, height) = distances.dimensions();
+    let img = image::ImageBuffer::from_fn(width, height, |x, y| {
+        let d = distances[(x, y)][0];
+        if d.is_finite() {
+            image::Luma([(d * scale).round() as u16])
+        } else {
+            image::Luma([u16::MAX])
+        }
+    });
+
+    image::DynamicImage::ImageLuma16(img)
+}
+
+/// Two pass chamfer distance transform where an orthogonal step costs
+/// `orthogonal` and a diagonal step costs `diagonal`, or is never taken if
+/// `diagonal` is `u32::MAX`. Distances are divided by `orthogonal`.
+fn chamfer(
+    foreground: &[bool],
+    width: u32,
+    height: u32,
+    orthogonal: u32,
+    diagonal: u32,
+) -> Vec<f32> {
+    let (width, height) = (width as usize, height as usize);
+    let mut distances: Vec<u32> = foreground
+        .iter()
+        .map(|&fg| if fg { u32::MAX } else { 0 })
+        .collect();
+
+    // Offsets of the neighbors which come before a pixel in row-major order
+    let before: [(isize, isize, u32); 4] = [
+        (-1, 0, orthogonal),
+        (-1, -1, diagonal),
+        (0, -1, orthogonal),
+        (1, -1, diagonal),
+    ];
+
+    let mut relax = |x: usize, y: usize, direction: isize| {
+        let index = y * width + x;
+        if distances[index] == 0 {
+            return;
+        }
+
+        for &(dx, dy, cost) in &before {
+            let (nx, ny) = (x as isize + dx * direction, y as isize + dy * direction);
+            if nx < 0 || ny < 0 || nx as usize >= width || ny as usize >= height {
+                continue;
+            }
+
+            let candidate = distances[ny as usize * width + nx as usize].saturating_add(cost);
+            distances[index] = distances[index].min(candidate);
+        }
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            relax(x, y, 1);
+        }
+    }
+    for y in (0..height).rev() {
+        for x in (0..width).rev() {
+            relax(x, y, -1);
+        }
+    }
+
+    distances
+        .into_iter()
+        .map(|d| match d {
+            u32::MAX => f32::INFINITY,
+            d => d as f32 / orthogonal as f32,
+        })
+        .collect()
+}
+
+/// Calculate the squared Euclidean distance from every pixel to the nearest
+/// pixel which isn't `foreground`. Background pixels have a distance of 0.0,
+/// and every pixel is infinitely far away if there is no background.
+pub(crate) fn squared_euclidean(foreground: &[bool], width: u32, height: u32) -> Vec<f64> {
+    let (width, height) = (width as usize, height as usize);
+    let mut distances: Vec<f64> = foreground
+        .iter()
+        .map(|&fg| if fg { f64::INFINITY } else { 0.0 })
+        .collect();
+
+    // Transform the columns and then the rows
+    let mut line = Vec::with_capacity(width.max(height));
+    for x in 0..width {
+        line.clear();
+        line.extend((0..height).map(|y| distances[y * width + x]));
+        for (y, d) in lower_envelope(&line).into_iter().enumerate() {
+            distances[y * width + x] = d;
+        }
+    }
+    for row in distances.chunks_exact_mut(width.max(1)) {
+        let transformed = lower_envelope(row);
+        row.copy_from_slice(&transformed);
+    }
+
+    distances
+}
+
+/// One dimensional squared distance transform of a sampled function, from the
+/// lower envelope of parabolas rooted at each finite sample.
+fn lower_envelope(f: &[f64]) -> Vec<f64> {
+    // Locations of the parabolas in the envelope and the boundaries between
+    // them
+    let mut vertices: Vec<usize> = Vec::with_capacity(f.len());
+    let mut boundaries: Vec<f64> = Vec::with_capacity(f.len() + 1);
+
+    for (q, &value) in f.iter().enumerate().filter(|(_, v)| v.is_finite()) {
+        let q_f = q as f64;
+        let mut s = f64::NEG_INFINITY;
+        while let Some(&v) = vertices.last() {
+            let v_f = v as f64;
+            s = ((value + q_f * q_f) - (f[v] + v_f * v_f)) / (2.0 * (q_f - v_f));
+            if s <= *boundaries.last().unwrap_or(&f64::NEG_INFINITY) {
+                vertices.pop();
+                boundaries.pop();
+                s = f64::NEG_INFINITY;
+            } else {
+                break;
+            }
+        }
+        vertices.push(q);
+        boundaries.push(s);
+    }
+
+    if vertices.is_empty() {
+        return vec![f64::INFINITY; f.len()];
+    }
+
+    let mut k = 0;
+    (0..f.len())
+        .map(|q| {
+            let q_f = q as f64;
+            while k + 1 < vertices.len() && boundaries[k + 1] < q_f {
+                k += 1;
+            }
+            let v = vertices[k];
+
+            (q_f - v as f64).powi(2) + f[v]
+        })
+        .collect()
+}
@@ -1,8 +1,22 @@
 //! Edge detection algorithms for preprocessing images.
 
-use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+mod canny;
+mod color;
+mod compass;
+mod convolution;
+mod gradient;
+mod zero_crossing;
+
+use crate::error::SkeletonizeError;
 use crate::ForegroundColor;
 
+pub use canny::canny;
+pub use color::{color_gradient, color_gradient_magnitude, ColorGradientMethod};
+pub use compass::{compass, kirsch, robinson, CompassOperator, CompassResponse};
+pub use compass::{KIRSCH_EAST, ROBINSON_EAST};
+pub use gradient::{gradient, gradient_magnitude, Directions, Gradient, GradientKernels};
+pub use zero_crossing::{difference_of_gaussians, laplacian_of_gaussian};
+
 /// Sobel vertical `North` gradient operator.
 #[rustfmt::skip]
 pub const SOBEL_NORTH: [f32; 9] = [
@@ -11,6 +25,7 @@ pub const SOBEL_NORTH: [f32; 9] = [
     -1.0, -2.0, -1.0,
 ];
 /// Sobel vertical `South` gradient operator.
+#[deprecated(note = "identical to the negated `SOBEL_NORTH`")]
 #[rustfmt::skip]
 pub const SOBEL_SOUTH: [f32; 9] = [
     -1.0, -2.0, -1.0,
@@ -25,6 +40,7 @@ pub const SOBEL_EAST: [f32; 9] = [
     -1.0, 0.0, 1.0,
 ];
 /// Sobel horizontal `West` gradient operator.
+#[deprecated(note = "identical to the negated `SOBEL_EAST`")]
 #[rustfmt::skip]
 pub const SOBEL_WEST: [f32; 9] = [
     1.0, 0.0, -1.0,
@@ -32,46 +48,142 @@ pub const SOBEL_WEST: [f32; 9] = [
     1.0, 0.0, -1.0,
 ];
 
+/// Prewitt vertical `North` gradient operator.
+#[rustfmt::skip]
+pub const PREWITT_NORTH: [f32; 9] = [
+    1.0, 1.0, 1.0,
+    0.0, 0.0, 0.0,
+    -1.0, -1.0, -1.0,
+];
+/// Prewitt horizontal `East` gradient operator.
+#[rustfmt::skip]
+pub const PREWITT_EAST: [f32; 9] = [
+    -1.0, 0.0, 1.0,
+    -1.0, 0.0, 1.0,
+    -1.0, 0.0, 1.0,
+];
+/// Scharr vertical `North` gradient operator.
+#[rustfmt::skip]
+pub const SCHARR_NORTH: [f32; 9] = [
+    3.0, 10.0, 3.0,
+    0.0, 0.0, 0.0,
+    -3.0, -10.0, -3.0,
+];
+/// Scharr horizontal `East` gradient operator.
+#[rustfmt::skip]
+pub const SCHARR_EAST: [f32; 9] = [
+    -3.0, 0.0, 3.0,
+    -10.0, 0.0, 10.0,
+    -3.0, 0.0, 3.0,
+];
+/// Roberts cross gradient operator along the diagonal from the top left to
+/// the bottom right. The 2x2 operator is placed in the bottom right of the
+/// kernel.
+#[rustfmt::skip]
+pub const ROBERTS_DIAGONAL: [f32; 9] = [
+    0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0,
+    0.0, 0.0, -1.0,
+];
+/// Roberts cross gradient operator along the diagonal from the top right to
+/// the bottom left. The 2x2 operator is placed in the bottom right of the
+/// kernel.
+#[rustfmt::skip]
+pub const ROBERTS_ANTIDIAGONAL: [f32; 9] = [
+    0.0, 0.0, 0.0,
+    0.0, 0.0, 1.0,
+    0.0, -1.0, 0.0,
+];
+
 /// Detect edges in an image using [`SOBEL_EAST`](SOBEL_EAST) and
 /// [`SOBEL_NORTH`](SOBEL_NORTH) gradient operators.
 /// The image should not have transparency.
 ///
-/// `threshold` is an optional parameter between 0.0 and 1.0 which is used to
-/// binarize the image. Pixels below that `Luma` threshold will be converted
-/// to the background color.
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. A step from black to white has a magnitude of 4.0, and magnitudes
+/// are at most √20 ≈ 4.47. Without a threshold, magnitudes above 1.0 saturate
+/// at the foreground color.
+///
+/// Edges are found in both directions, whether the image changes from dark to
+/// light or from light to dark.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::edge_detection::sobel;
+/// use skeletonize::foreground;
+///
+/// // A dark square on a light background
+/// let luma = image::GrayImage::from_fn(12, 12, |x, y| {
+///     image::Luma([if (4..8).contains(&x) && (4..8).contains(&y) { 0 } else { 255 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let edges = sobel::<foreground::White>(&img, Some(0.5))?;
+/// let edges = edges.as_luma8().unwrap();
+/// assert_eq!(edges[(4, 6)][0], 255);
+/// assert_eq!(edges[(6, 6)][0], 0);
+/// assert_eq!(edges[(1, 1)][0], 0);
+/// # Ok(())
+/// # }
+/// ```
 pub fn sobel<F: ForegroundColor>(
     img: &image::DynamicImage,
     threshold: Option<f32>,
 ) -> Result<image::DynamicImage, SkeletonizeError> {
-    let mut filter_up = img.filter3x3(&SOBEL_NORTH);
-    let filtered_right = img.filter3x3(&SOBEL_EAST);
-    let mutable_error = SkeletonizeError::LumaConversion(LumaConversionErrorKind::SobelMutableLuma);
-    let immutable_error = SkeletonizeError::LumaConversion(LumaConversionErrorKind::SobelLuma);
-
-    let iter_down = filter_up.as_mut_luma8().ok_or(mutable_error)?.iter_mut();
-    let iter_right = filtered_right.as_luma8().ok_or(immutable_error)?.iter();
-
-    for (g_down, g_right) in iter_down.zip(iter_right) {
-        let res = (f32::from(*g_down) / 255.0).hypot(f32::from(*g_right) / 255.0);
+    gradient_magnitude::<F>(img, &GradientKernels::sobel(), threshold)
+}
 
-        if let Some(threshold) = threshold {
-            *g_down = if res < threshold {
-                F::BACKGROUND_COLOR
-            } else {
-                !F::BACKGROUND_COLOR
-            }
-        } else {
-            *g_down = (res * 255.0).round() as u8;
-        }
-    }
+/// Detect edges in an image using [`PREWITT_EAST`](PREWITT_EAST) and
+/// [`PREWITT_NORTH`](PREWITT_NORTH) gradient operators.
+/// The image should not have transparency.
+///
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. A step from black to white has a magnitude of 3.0, and magnitudes
+/// are at most √10 ≈ 3.16. Without a threshold, magnitudes above 1.0 saturate
+/// at the foreground color.
+pub fn prewitt<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    threshold: Option<f32>,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    gradient_magnitude::<F>(img, &GradientKernels::prewitt(), threshold)
+}
 
-    // If ForegroundColor is Black and threshold None, edges would stay white
-    // so we need to invert the result before returning it.
-    if threshold.is_none() && F::BACKGROUND_COLOR == 255 {
-        filter_up.invert()
-    }
+/// Detect edges in an image using [`SCHARR_EAST`](SCHARR_EAST) and
+/// [`SCHARR_NORTH`](SCHARR_NORTH) gradient operators, which respond more
+/// evenly to edges in every direction than Sobel operators.
+/// The image should not have transparency.
+///
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. The Scharr operators have larger weights than the Sobel operators,
+/// so a step from black to white has a magnitude of 16.0, and magnitudes are
+/// at most √356 ≈ 18.9. Without a threshold, magnitudes above 1.0 saturate at
+/// the foreground color.
+pub fn scharr<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    threshold: Option<f32>,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    gradient_magnitude::<F>(img, &GradientKernels::scharr(), threshold)
+}
 
-    Ok(filter_up)
+/// Detect edges in an image using the [`ROBERTS_DIAGONAL`](ROBERTS_DIAGONAL)
+/// and [`ROBERTS_ANTIDIAGONAL`](ROBERTS_ANTIDIAGONAL) cross gradient
+/// operators. The 2x2 operators find thinner edges than the 3x3 operators but
+/// are more sensitive to noise.
+/// The image should not have transparency.
+///
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. A step from black to white has a magnitude of √2 ≈ 1.41, which is
+/// also the largest magnitude. Without a threshold, magnitudes above 1.0
+/// saturate at the foreground color.
+pub fn roberts<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    threshold: Option<f32>,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    gradient_magnitude::<F>(img, &GradientKernels::roberts(), threshold)
 }
 
 /// Detect edges in an image using four Sobel gradient operators:
@@ -79,48 +191,103 @@ pub fn sobel<F: ForegroundColor>(
 /// [`SOBEL_EAST`](SOBEL_EAST), and [`SOBEL_WEST`](SOBEL_WEST).
 /// The image should not have transparency.
 ///
-/// `threshold` is an optional parameter between 0.0 and 1.0 which is used to
-/// binarize the image. Pixels below that `Luma` threshold will be converted
-/// to the background color.
+/// Opposite operators measure the same gradient with the opposite sign. The
+/// gradients are calculated in floating point so both signs are kept, which
+/// makes the result the same as [`sobel`](sobel).
+///
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. A step from black to white has a magnitude of 4.0, and magnitudes
+/// are at most √20 ≈ 4.47. Without a threshold, magnitudes above 1.0 saturate
+/// at the foreground color.
+#[deprecated(note = "identical to sobel")]
 pub fn sobel4<F: ForegroundColor>(
     img: &image::DynamicImage,
     threshold: Option<f32>,
 ) -> Result<image::DynamicImage, SkeletonizeError> {
-    let mut filter_up = img.filter3x3(&SOBEL_NORTH);
-    let filter_down = img.filter3x3(&SOBEL_SOUTH);
-    let filter_right = img.filter3x3(&SOBEL_EAST);
-    let filter_left = img.filter3x3(&SOBEL_WEST);
-
-    let mutable_error = SkeletonizeError::LumaConversion(LumaConversionErrorKind::SobelMutableLuma);
-    let immutable_error = SkeletonizeError::LumaConversion(LumaConversionErrorKind::SobelLuma);
+    gradient_magnitude::<F>(img, &GradientKernels::sobel(), threshold)
+}
 
-    let iter_up = filter_up.as_mut_luma8().ok_or(mutable_error)?.iter_mut();
-    let iter_down = filter_down.as_luma8().ok_or(immutable_error)?.iter();
-    let iter_right = filter_right.as_luma8().ok_or(immutable_error)?.iter();
-    let iter_left = filter_left.as_luma8().ok_or(immutable_error)?.iter();
+/// Convert a gradient magnitude with luma values from 0.0 to 1.0 into an image.
+/// If `threshold` is `Some`, the image is binarized so that pixels below the
+/// threshold have the background color and all other pixels have the
+/// foreground color. Otherwise, larger magnitudes are closer to the foreground
+/// color, and magnitudes of 1.0 and above have the foreground color.
+fn magnitude_to_image<F: ForegroundColor>(
+    magnitude: &convolution::FloatImage,
+    threshold: Option<f32>,
+) -> image::DynamicImage {
+    let (width, height) = magnitude.dimensions();
 
-    for (((g_up, g_down), g_left), g_right) in iter_up.zip(iter_down).zip(iter_right).zip(iter_left)
-    {
-        let vertical = (f32::from(*g_up) - f32::from(*g_down)) / 255.0;
-        let horizontal = (f32::from(*g_right) - f32::from(*g_left)) / 255.0;
-        let res = vertical.hypot(horizontal);
+    let luma_img = image::GrayImage::from_fn(width, height, |x, y| {
+        let res = magnitude[(x, y)][0];
 
         if let Some(threshold) = threshold {
-            *g_up = if res < threshold {
-                F::BACKGROUND_COLOR
+            if res < threshold {
+                image::Luma([F::BACKGROUND_COLOR])
             } else {
-                !F::BACKGROUND_COLOR
+                image::Luma([!F::BACKGROUND_COLOR])
             }
         } else {
-            *g_up = (res * 255.0).round() as u8;
+            // Saturates at white for magnitudes above 1.0
+            let level = (res * 255.0).round() as u8;
+
+            // If ForegroundColor is Black, edges would stay white so we need
+            // to invert the result.
+            image::Luma([if F::BACKGROUND_COLOR == 255 {
+                !level
+            } else {
+                level
+            }])
         }
-    }
+    });
 
-    // If ForegroundColor is Black and threshold None, edges would stay white
-    // so we need to invert the result before returning it.
-    if threshold.is_none() && F::BACKGROUND_COLOR == 255 {
-        filter_up.invert()
-    }
+    image::DynamicImage::ImageLuma8(luma_img)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foreground::White;
 
-    Ok(filter_up)
+    type Detector =
+        fn(&image::DynamicImage, Option<f32>) -> Result<image::DynamicImage, SkeletonizeError>;
+
+    #[test]
+    #[allow(deprecated)]
+    fn step_edges_of_both_polarities() {
+        let detectors: [Detector; 5] = [
+            sobel::<White>,
+            sobel4::<White>,
+            prewitt::<White>,
+            scharr::<White>,
+            roberts::<White>,
+        ];
+
+        // Vertical and horizontal steps from dark to light
+        let steps: [fn(u32, u32) -> bool; 2] = [|x, _| x < 4, |_, y| y < 4];
+
+        for step in steps {
+            let luma = image::GrayImage::from_fn(8, 8, |x, y| {
+                image::Luma([if step(x, y) { 0 } else { 255 }])
+            });
+            let dark_to_light = image::DynamicImage::ImageLuma8(luma);
+            let mut light_to_dark = dark_to_light.clone();
+            light_to_dark.invert();
+
+            for detect in detectors {
+                let edges = detect(&dark_to_light, Some(0.5)).unwrap();
+                let count = edges
+                    .as_luma8()
+                    .unwrap()
+                    .iter()
+                    .filter(|&&p| p == 255)
+                    .count();
+                assert!(count >= 8);
+
+                // Both polarities of the step find the same edge
+                assert_eq!(edges, detect(&light_to_dark, Some(0.5)).unwrap());
+            }
+        }
+    }
 }
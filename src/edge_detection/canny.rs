@@ -0,0 +1,140 @@
+//! Canny edge detection.
+
+use super::convolution::{gaussian_blur, luma_to_float, FloatImage};
+use super::gradient::float_gradient;
+use super::{Directions, Gradient, GradientKernels};
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::ForegroundColor;
+
+/// Detect edges in a grayscale image using the Canny edge detector. The result
+/// is a binary image where edge pixels have the foreground color and all other
+/// pixels have the background color.
+///
+/// The image is smoothed by a Gaussian with a standard deviation of `sigma`
+/// pixels, then the gradient is calculated with the
+/// [`SOBEL_EAST`](super::SOBEL_EAST) and [`SOBEL_NORTH`](super::SOBEL_NORTH)
+/// operators. Pixels which aren't the maximum of the gradient magnitude along
+/// the gradient direction are suppressed, which leaves edges that are mostly
+/// one pixel wide. A `sigma` of 0.0 disables smoothing.
+///
+/// Pixels with a gradient magnitude of at least `high_threshold` are edges.
+/// Pixels with a magnitude of at least `low_threshold` are edges if they're
+/// connected to another edge pixel. The gradient magnitude is calculated on
+/// luma values from 0.0 to 1.0, so a step between black and white has a
+/// magnitude of 4.0. Thresholds between 0.1 and 1.0 are a reasonable start.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::edge_detection::canny;
+/// use skeletonize::foreground;
+///
+/// // A light square on a dark background
+/// let luma = image::GrayImage::from_fn(32, 32, |x, y| {
+///     image::Luma([if (8..24).contains(&x) && (8..24).contains(&y) { 200 } else { 40 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let edges = canny::<foreground::White>(&img, 1.0, 0.2, 0.5)?;
+/// let edges = edges.as_luma8().unwrap();
+///
+/// // The left side of the square is found and is one pixel wide
+/// let row: Vec<u32> = (0..32).filter(|&x| edges[(x, 16)][0] == 255).collect();
+/// assert_eq!(row.len(), 2);
+/// assert!((7..=8).contains(&row[0]));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ### Reference
+///
+/// Canny, J. (1986). A Computational Approach to Edge Detection. IEEE
+/// Transactions on Pattern Analysis and Machine Intelligence. PAMI-8. 679-698.
+/// [DOI:10.1109/TPAMI.1986.4767851](https://doi.org/10.1109/TPAMI.1986.4767851)
+pub fn canny<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    sigma: f32,
+    low_threshold: f32,
+    high_threshold: f32,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    let luma_img = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::CannyLuma,
+    ))?;
+    let (width, height) = luma_img.dimensions();
+
+    let smoothed = gaussian_blur(&luma_to_float(luma_img), sigma);
+    let gradient = float_gradient(&smoothed, &GradientKernels::sobel());
+
+    let suppressed = non_maximum_suppression(&gradient);
+    let edges = hysteresis(&suppressed, low_threshold, high_threshold);
+
+    let binary = image::GrayImage::from_fn(width, height, |x, y| {
+        if edges[y as usize * width as usize + x as usize] {
+            image::Luma([!F::BACKGROUND_COLOR])
+        } else {
+            image::Luma([F::BACKGROUND_COLOR])
+        }
+    });
+
+    Ok(image::DynamicImage::ImageLuma8(binary))
+}
+
+/// Set the magnitude of pixels which are smaller than either neighbor along
+/// the gradient direction to zero. Pixels on the border of the image are
+/// always suppressed.
+fn non_maximum_suppression(gradient: &Gradient) -> FloatImage {
+    let magnitude = &gradient.magnitude;
+    let directions = gradient.directions(Directions::Four);
+    let (width, height) = magnitude.dimensions();
+
+    FloatImage::from_fn(width, height, |x, y| {
+        let m = magnitude[(x, y)][0];
+        if x == 0 || y == 0 || x + 1 >= width || y + 1 >= height || m == 0.0 {
+            return image::Luma([0.0]);
+        }
+
+        // Compare against the pixels on either side along the gradient
+        let (before, after) = match directions[(x, y)][0] {
+            0 => ((x - 1, y), (x + 1, y)),
+            1 => ((x - 1, y + 1), (x + 1, y - 1)),
+            2 => ((x, y + 1), (x, y - 1)),
+            _ => ((x + 1, y + 1), (x - 1, y - 1)),
+        };
+
+        // Ties keep only one of two equal pixels so plateaus stay thin
+        if m > magnitude[before][0] && m >= magnitude[after][0] {
+            image::Luma([m])
+        } else {
+            image::Luma([0.0])
+        }
+    })
+}
+
+/// Mark pixels with a magnitude of at least `high` as edges, then follow
+/// 8-connected pixels with a magnitude of at least `low` from those edges.
+fn hysteresis(magnitude: &FloatImage, low: f32, high: f32) -> Vec<bool> {
+    let (width, height) = magnitude.dimensions();
+    let mut edges = vec![false; width as usize * height as usize];
+    let mut stack = Vec::new();
+
+    for (x, y, m) in magnitude.enumerate_pixels() {
+        if m[0] >= high && m[0] > 0.0 {
+            edges[y as usize * width as usize + x as usize] = true;
+            stack.push((x, y));
+        }
+    }
+
+    while let Some((x, y)) = stack.pop() {
+        for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+            for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                let index = ny as usize * width as usize + nx as usize;
+                let m = magnitude[(nx, ny)][0];
+                if !edges[index] && m >= low && m > 0.0 {
+                    edges[index] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+    }
+
+    edges
+}
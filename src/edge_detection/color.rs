@@ -0,0 +1,155 @@
+//! Edge detection on color images.
+
+use super::convolution::{convolve, FloatImage};
+use super::{magnitude_to_image, Gradient, GradientKernels};
+use crate::error::SkeletonizeError;
+use crate::ForegroundColor;
+
+/// The method used to combine the gradients of the red, green, and blue
+/// channels of an image.
+///
+/// ### Reference
+///
+/// <span id="dizenzo"></span>Di Zenzo, Silvano. (1986). A note on the gradient
+/// of a multi-image. Computer Vision, Graphics, and Image Processing. 33.
+/// 116-125.
+/// [DOI:10.1016/0734-189X(86)90223-9](https://doi.org/10.1016/0734-189X(86)90223-9)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorGradientMethod {
+    /// The direction of greatest change across all channels together, from
+    /// the largest eigenvalue of the structure tensor. The tensors of the
+    /// channels are averaged so that gray images have the same magnitude as
+    /// their luma gradient.
+    ///
+    /// See [ColorGradientMethod](crate::edge_detection::ColorGradientMethod#dizenzo)
+    /// for reference.
+    #[default]
+    DiZenzo,
+    /// The gradient of whichever channel has the largest magnitude.
+    MaxChannel,
+}
+
+/// Calculate the magnitude and direction of the gradient of a color image
+/// measured by a pair of [`GradientKernels`](GradientKernels) on each of the
+/// red, green, and blue channels. Images which aren't RGB are converted to RGB
+/// first, and transparency is ignored.
+///
+/// Edges between colors with the same luma, which disappear from grayscale
+/// images, are found by looking at the channels separately. Channels are
+/// scaled to range from 0.0 to 1.0, and pixels outside of the image take the
+/// value of the nearest edge pixel.
+///
+/// The structure tensor of [`DiZenzo`](ColorGradientMethod::DiZenzo) only
+/// gives the orientation of an edge, so its angle points toward the side
+/// where the sum of the channels increases.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::edge_detection::{color_gradient, gradient, ColorGradientMethod};
+/// use skeletonize::edge_detection::GradientKernels;
+///
+/// // Red on the left and green on the right, with almost equal luma
+/// let rgb = image::RgbImage::from_fn(8, 8, |x, _| {
+///     image::Rgb(if x < 4 { [255, 0, 0] } else { [0, 76, 0] })
+/// });
+/// let img = image::DynamicImage::ImageRgb8(rgb);
+///
+/// // The edge barely shows up in the grayscale image
+/// let luma = image::DynamicImage::ImageLuma8(img.to_luma8());
+/// let gray = gradient(&luma, &GradientKernels::sobel())?;
+/// assert!(gray.magnitude[(4, 4)][0] < 0.05);
+///
+/// for method in [ColorGradientMethod::DiZenzo, ColorGradientMethod::MaxChannel] {
+///     let color = color_gradient(&img, &GradientKernels::sobel(), method)?;
+///     assert!(color.magnitude[(4, 4)][0] > 1.0);
+///     assert_eq!(color.magnitude[(1, 4)][0], 0.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn color_gradient(
+    img: &image::DynamicImage,
+    kernels: &GradientKernels,
+    method: ColorGradientMethod,
+) -> Result<Gradient, SkeletonizeError> {
+    let rgb = match img.as_rgb8() {
+        Some(rgb) => std::borrow::Cow::Borrowed(rgb),
+        None => std::borrow::Cow::Owned(img.to_rgb8()),
+    };
+    let (width, height) = rgb.dimensions();
+
+    let channels: Vec<(FloatImage, FloatImage)> = (0..3)
+        .map(|c| {
+            let channel = FloatImage::from_fn(width, height, |x, y| {
+                image::Luma([f32::from(rgb[(x, y)][c]) / 255.0])
+            });
+            (
+                convolve(&channel, kernels.x(), kernels.size()),
+                convolve(&channel, kernels.y(), kernels.size()),
+            )
+        })
+        .collect();
+
+    let mut magnitude = FloatImage::new(width, height);
+    let mut angle = FloatImage::new(width, height);
+
+    for (x, y, m) in magnitude.enumerate_pixels_mut() {
+        let gradients = channels
+            .iter()
+            .map(|(gx, gy)| (gx[(x, y)][0], gy[(x, y)][0]));
+
+        let (length, theta) = match method {
+            ColorGradientMethod::DiZenzo => {
+                let (mut gxx, mut gyy, mut gxy) = (0.0, 0.0, 0.0);
+                let (mut sum_x, mut sum_y) = (0.0, 0.0);
+                for (gx, gy) in gradients {
+                    gxx += gx * gx / 3.0;
+                    gyy += gy * gy / 3.0;
+                    gxy += gx * gy / 3.0;
+                    sum_x += gx;
+                    sum_y += gy;
+                }
+
+                // Largest eigenvalue of the tensor and its eigenvector
+                let lambda = 0.5 * (gxx + gyy + (gxx - gyy).hypot(2.0 * gxy));
+                let mut theta = 0.5 * (2.0 * gxy).atan2(gxx - gyy);
+                if sum_x * theta.cos() + sum_y * theta.sin() < 0.0 {
+                    theta += core::f32::consts::PI;
+                    if theta > core::f32::consts::PI {
+                        theta -= 2.0 * core::f32::consts::PI;
+                    }
+                }
+
+                (lambda.max(0.0).sqrt(), theta)
+            }
+            ColorGradientMethod::MaxChannel => gradients
+                .map(|(gx, gy)| (gx.hypot(gy), gy.atan2(gx)))
+                .fold((0.0, 0.0), |max, g| if g.0 > max.0 { g } else { max }),
+        };
+
+        m[0] = length;
+        angle[(x, y)][0] = theta;
+    }
+
+    Ok(Gradient { magnitude, angle })
+}
+
+/// Detect edges in a color image from the magnitude of the
+/// [`color_gradient`](color_gradient) measured by a pair of
+/// [`GradientKernels`](GradientKernels).
+///
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. As with [`gradient_magnitude`](super::gradient_magnitude), the range
+/// of the magnitude depends on the kernels. Without a threshold, magnitudes
+/// above 1.0 saturate at the foreground color.
+pub fn color_gradient_magnitude<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    kernels: &GradientKernels,
+    method: ColorGradientMethod,
+    threshold: Option<f32>,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    let gradient = color_gradient(img, kernels, method)?;
+
+    Ok(magnitude_to_image::<F>(&gradient.magnitude, threshold))
+}
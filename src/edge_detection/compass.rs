@@ -0,0 +1,175 @@
+//! Compass edge detection with eight rotations of a kernel.
+
+use super::convolution::{convolve, luma_to_float, FloatImage};
+use super::magnitude_to_image;
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::ForegroundColor;
+
+/// Kirsch compass operator for edges which are brighter toward the `East`.
+#[rustfmt::skip]
+pub const KIRSCH_EAST: [f32; 9] = [
+    -3.0, -3.0, 5.0,
+    -3.0, 0.0, 5.0,
+    -3.0, -3.0, 5.0,
+];
+/// Robinson compass operator for edges which are brighter toward the `East`.
+#[rustfmt::skip]
+pub const ROBINSON_EAST: [f32; 9] = [
+    -1.0, 0.0, 1.0,
+    -2.0, 0.0, 2.0,
+    -1.0, 0.0, 1.0,
+];
+
+/// Positions of the outer ring of a 3x3 kernel, counterclockwise from east.
+const RING: [usize; 8] = [5, 2, 1, 0, 3, 6, 7, 8];
+
+/// Operators which are rotated into eight kernels for compass edge detection.
+///
+/// ### Reference
+///
+/// <span id="kirsch"></span>Kirsch, Russell A. (1971). Computer determination
+/// of the constituent structure of biological images. Computers and Biomedical
+/// Research. 4. 315-328.
+/// [DOI:10.1016/0010-4809(71)90034-6](https://doi.org/10.1016/0010-4809(71)90034-6)
+///
+/// <span id="robinson"></span>Robinson, Guner S. (1977). Edge detection by
+/// compass gradient masks. Computer Graphics and Image Processing. 6. 492-501.
+/// [DOI:10.1016/S0146-664X(77)80024-5](https://doi.org/10.1016/S0146-664X(77)80024-5)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompassOperator {
+    /// Rotations of [`KIRSCH_EAST`](super::KIRSCH_EAST).
+    ///
+    /// See [CompassOperator](crate::edge_detection::CompassOperator#kirsch)
+    /// for reference.
+    Kirsch,
+    /// Rotations of [`ROBINSON_EAST`](super::ROBINSON_EAST).
+    ///
+    /// See [CompassOperator](crate::edge_detection::CompassOperator#robinson)
+    /// for reference.
+    Robinson,
+}
+
+impl CompassOperator {
+    /// The eight kernels of the operator. Kernel `0` responds to edges which
+    /// are brighter toward the east, and each following kernel is rotated by
+    /// 45 degrees counterclockwise so that kernel `2` faces north.
+    pub fn kernels(&self) -> [[f32; 9]; 8] {
+        let base = match self {
+            Self::Kirsch => KIRSCH_EAST,
+            Self::Robinson => ROBINSON_EAST,
+        };
+
+        let mut kernels = [base; 8];
+        for (rotation, kernel) in kernels.iter_mut().enumerate() {
+            for (i, &position) in RING.iter().enumerate() {
+                kernel[RING[(i + rotation) % 8]] = base[position];
+            }
+        }
+
+        kernels
+    }
+}
+
+/// The strongest response of a compass operator at each pixel.
+#[derive(Clone, Debug)]
+pub struct CompassResponse {
+    /// The largest response of the eight kernels, calculated on luma values
+    /// from 0.0 to 1.0.
+    pub magnitude: image::ImageBuffer<image::Luma<f32>, Vec<f32>>,
+    /// The index of the kernel with the largest response, from `0` to `7`.
+    /// The indices match [`Directions::Eight`](super::Directions::Eight), so
+    /// `0` faces east and `2` faces north.
+    pub direction: image::GrayImage,
+}
+
+/// Calculate the strongest response and its direction of a compass operator on
+/// a grayscale image. Pixels outside of the image take the value of the
+/// nearest edge pixel.
+pub fn compass(
+    img: &image::DynamicImage,
+    operator: CompassOperator,
+) -> Result<CompassResponse, SkeletonizeError> {
+    let luma_img = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::CompassLuma,
+    ))?;
+    let luma = luma_to_float(luma_img);
+    let (width, height) = luma.dimensions();
+
+    let mut magnitude = FloatImage::from_pixel(width, height, image::Luma([f32::MIN]));
+    let mut direction = image::GrayImage::new(width, height);
+
+    for (index, kernel) in operator.kernels().iter().enumerate() {
+        let response = convolve(&luma, kernel, 3);
+        for ((m, d), r) in magnitude
+            .pixels_mut()
+            .zip(direction.pixels_mut())
+            .zip(response.pixels())
+        {
+            if r[0] > m[0] {
+                m[0] = r[0];
+                d[0] = index as u8;
+            }
+        }
+    }
+
+    Ok(CompassResponse {
+        magnitude,
+        direction,
+    })
+}
+
+/// Detect edges in an image using the largest response of the eight Kirsch
+/// compass operators. The image should not have transparency.
+///
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. A step from black to white has a response of 15.0, which is also the
+/// largest response, so thresholds above 1.0 can be used to keep only stronger
+/// edges.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::edge_detection::{compass, kirsch, CompassOperator};
+/// use skeletonize::foreground;
+///
+/// // Brighter toward the bottom of the image
+/// let luma = image::GrayImage::from_fn(8, 8, |_, y| {
+///     image::Luma([if y < 4 { 0 } else { 255 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let edges = kirsch::<foreground::White>(&img, Some(2.0))?;
+/// assert_eq!(edges.as_luma8().unwrap()[(4, 4)][0], 255);
+/// assert_eq!(edges.as_luma8().unwrap()[(4, 0)][0], 0);
+///
+/// // The winning kernel faces south
+/// let response = compass(&img, CompassOperator::Kirsch)?;
+/// assert_eq!(response.direction[(4, 3)][0], 6);
+/// # Ok(())
+/// # }
+/// ```
+pub fn kirsch<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    threshold: Option<f32>,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    let response = compass(img, CompassOperator::Kirsch)?;
+
+    Ok(magnitude_to_image::<F>(&response.magnitude, threshold))
+}
+
+/// Detect edges in an image using the largest response of the eight Robinson
+/// compass operators. The image should not have transparency.
+///
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. A step from black to white has a response of 4.0, which is also the
+/// largest response, so thresholds above 1.0 can be used to keep only
+/// stronger edges.
+pub fn robinson<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    threshold: Option<f32>,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    let response = compass(img, CompassOperator::Robinson)?;
+
+    Ok(magnitude_to_image::<F>(&response.magnitude, threshold))
+}
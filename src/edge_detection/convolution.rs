@@ -0,0 +1,61 @@
+//! Convolution of grayscale images in floating point.
+
+/// A single channel image with `f32` values.
+pub(crate) type FloatImage = image::ImageBuffer<image::Luma<f32>, Vec<f32>>;
+
+/// Convert a grayscale image into a `FloatImage` with values from 0.0 to 1.0.
+pub(crate) fn luma_to_float(img: &image::GrayImage) -> FloatImage {
+    let (width, height) = img.dimensions();
+    let data = img.iter().map(|&p| f32::from(p) / 255.0).collect();
+
+    FloatImage::from_raw(width, height, data).expect("buffer matches image dimensions")
+}
+
+/// Correlate `img` with a `kernel` which is `kernel_width` values wide and
+/// stored in row-major order. Both dimensions of the kernel must be odd.
+/// Pixels outside of the image take the value of the nearest edge pixel.
+pub(crate) fn convolve(img: &FloatImage, kernel: &[f32], kernel_width: usize) -> FloatImage {
+    let (width, height) = img.dimensions();
+    let kernel_height = kernel.len() / kernel_width;
+    let (radius_x, radius_y) = ((kernel_width / 2) as i64, (kernel_height / 2) as i64);
+    let (max_x, max_y) = (i64::from(width) - 1, i64::from(height) - 1);
+
+    FloatImage::from_fn(width, height, |x, y| {
+        let mut sum = 0.0;
+        for (ky, row) in kernel.chunks_exact(kernel_width).enumerate() {
+            let sy = (i64::from(y) + ky as i64 - radius_y).clamp(0, max_y) as u32;
+            for (kx, &k) in row.iter().enumerate() {
+                let sx = (i64::from(x) + kx as i64 - radius_x).clamp(0, max_x) as u32;
+                sum += k * img.get_pixel(sx, sy)[0];
+            }
+        }
+
+        image::Luma([sum])
+    })
+}
+
+/// Blur `img` with a Gaussian of standard deviation `sigma`. A `sigma` of
+/// 0.0 or less returns a copy of the image.
+pub(crate) fn gaussian_blur(img: &FloatImage, sigma: f32) -> FloatImage {
+    if sigma <= 0.0 {
+        return img.clone();
+    }
+
+    // The Gaussian is separable, so blur the rows and then the columns
+    let kernel = gaussian_kernel(sigma);
+    let blurred = convolve(img, &kernel, kernel.len());
+
+    convolve(&blurred, &kernel, 1)
+}
+
+/// Create a normalized one dimensional Gaussian kernel which extends three
+/// standard deviations from its center.
+pub(crate) fn gaussian_kernel(sigma: f32) -> Vec<f32> {
+    let radius = (3.0 * sigma).ceil().max(1.0) as i32;
+    let kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+
+    kernel.into_iter().map(|k| k / sum).collect()
+}
@@ -0,0 +1,226 @@
+//! Edge detection with user-supplied gradient operators.
+
+use super::convolution::{convolve, luma_to_float, FloatImage};
+use super::{magnitude_to_image, PREWITT_EAST, PREWITT_NORTH, ROBERTS_ANTIDIAGONAL};
+use super::{ROBERTS_DIAGONAL, SCHARR_EAST, SCHARR_NORTH, SOBEL_EAST, SOBEL_NORTH};
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::ForegroundColor;
+
+/// A pair of square kernels which measure the gradient of an image along the
+/// horizontal `x` axis and vertical `y` axis.
+///
+/// Kernels are stored in row-major order and have an odd width and height so
+/// they can be centered on a pixel.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::edge_detection::{gradient_magnitude, GradientKernels};
+/// use skeletonize::foreground;
+///
+/// // 5x5 derivative operators
+/// #[rustfmt::skip]
+/// let x = vec![
+///     -1.0, -2.0, 0.0, 2.0, 1.0,
+///     -4.0, -8.0, 0.0, 8.0, 4.0,
+///     -6.0, -12.0, 0.0, 12.0, 6.0,
+///     -4.0, -8.0, 0.0, 8.0, 4.0,
+///     -1.0, -2.0, 0.0, 2.0, 1.0,
+/// ];
+/// let y = (0..25).map(|i| -x[(i % 5) * 5 + i / 5]).collect();
+/// let kernels = GradientKernels::new(x, y, 5)?;
+///
+/// let luma = image::GrayImage::from_fn(16, 16, |x, _| image::Luma([if x < 8 { 0 } else { 255 }]));
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let edges = gradient_magnitude::<foreground::White>(&img, &kernels, Some(0.5))?;
+/// let edges = edges.as_luma8().unwrap();
+/// assert_eq!(edges[(8, 8)][0], 255);
+/// assert_eq!(edges[(2, 8)][0], 0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradientKernels {
+    x: Vec<f32>,
+    y: Vec<f32>,
+    size: usize,
+}
+
+impl GradientKernels {
+    /// Create a new pair of kernels which are `size` values wide and tall.
+    ///
+    /// Returns an error if `size` isn't odd or either kernel doesn't have
+    /// `size * size` values.
+    pub fn new(x: Vec<f32>, y: Vec<f32>, size: usize) -> Result<Self, SkeletonizeError> {
+        if size % 2 != 1 || x.len() != size * size || y.len() != size * size {
+            return Err(SkeletonizeError::InvalidKernel);
+        }
+
+        Ok(Self { x, y, size })
+    }
+
+    /// The [`SOBEL_EAST`](super::SOBEL_EAST) and
+    /// [`SOBEL_NORTH`](super::SOBEL_NORTH) operators.
+    pub fn sobel() -> Self {
+        Self::from_3x3(SOBEL_EAST, SOBEL_NORTH)
+    }
+
+    /// The [`PREWITT_EAST`](super::PREWITT_EAST) and
+    /// [`PREWITT_NORTH`](super::PREWITT_NORTH) operators.
+    pub fn prewitt() -> Self {
+        Self::from_3x3(PREWITT_EAST, PREWITT_NORTH)
+    }
+
+    /// The [`SCHARR_EAST`](super::SCHARR_EAST) and
+    /// [`SCHARR_NORTH`](super::SCHARR_NORTH) operators.
+    pub fn scharr() -> Self {
+        Self::from_3x3(SCHARR_EAST, SCHARR_NORTH)
+    }
+
+    /// The [`ROBERTS_ANTIDIAGONAL`](super::ROBERTS_ANTIDIAGONAL) and
+    /// [`ROBERTS_DIAGONAL`](super::ROBERTS_DIAGONAL) cross operators.
+    pub fn roberts() -> Self {
+        Self::from_3x3(ROBERTS_ANTIDIAGONAL, ROBERTS_DIAGONAL)
+    }
+
+    /// The kernel for the horizontal gradient.
+    pub fn x(&self) -> &[f32] {
+        &self.x
+    }
+
+    /// The kernel for the vertical gradient.
+    pub fn y(&self) -> &[f32] {
+        &self.y
+    }
+
+    /// The width and height of the kernels.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn from_3x3(x: [f32; 9], y: [f32; 9]) -> Self {
+        Self {
+            x: x.to_vec(),
+            y: y.to_vec(),
+            size: 3,
+        }
+    }
+}
+
+/// The gradient of each pixel in an image, measured by a pair of
+/// [`GradientKernels`](GradientKernels).
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::edge_detection::{gradient, Directions, GradientKernels};
+///
+/// // Brighter toward the top of the image
+/// let luma = image::GrayImage::from_fn(8, 8, |_, y| image::Luma([255 - 30 * y as u8]));
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let gradient = gradient(&img, &GradientKernels::sobel())?;
+/// let angle = gradient.angle[(4, 4)][0];
+/// assert!((angle - core::f32::consts::FRAC_PI_2).abs() < 1e-4);
+///
+/// // Index 2 of 8 points north
+/// assert_eq!(gradient.directions(Directions::Eight)[(4, 4)][0], 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    /// The magnitude of the gradient, calculated on luma values from 0.0 to
+    /// 1.0.
+    pub magnitude: image::ImageBuffer<image::Luma<f32>, Vec<f32>>,
+    /// The direction of the gradient in radians from `-π` to `π`, pointing
+    /// toward brighter pixels. The angle is measured in the frame of the kernel
+    /// pair, where `0` points along the `x` kernel and `π / 2` points along the
+    /// `y` kernel. That is east and north for the Sobel, Prewitt, and Scharr
+    /// kernels, but north-east and north-west for the Roberts cross kernels.
+    pub angle: image::ImageBuffer<image::Luma<f32>, Vec<f32>>,
+}
+
+/// The number of directions that gradient angles are quantized into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Directions {
+    /// Orientations, ignoring whether the gradient points toward or away from
+    /// a direction. `0` is east or west, `1` is north-east or south-west, `2`
+    /// is north or south, and `3` is north-west or south-east.
+    Four,
+    /// Directions counterclockwise from `0` pointing east, so that `2` is
+    /// north, `4` is west, and `6` is south.
+    Eight,
+}
+
+impl Gradient {
+    /// Quantize the angle of each pixel into the nearest of four or eight
+    /// [`Directions`](Directions), in the same frame as the `angle`. Pixels
+    /// without a gradient have an angle of `0` and a direction of `0`.
+    pub fn directions(&self, directions: Directions) -> image::GrayImage {
+        let (width, height) = self.angle.dimensions();
+
+        image::GrayImage::from_fn(width, height, |x, y| {
+            let eighths = (self.angle[(x, y)][0] / core::f32::consts::FRAC_PI_4).round() as i32;
+            let direction = match directions {
+                Directions::Four => eighths.rem_euclid(4),
+                Directions::Eight => eighths.rem_euclid(8),
+            };
+
+            image::Luma([direction as u8])
+        })
+    }
+}
+
+/// Calculate the magnitude and direction of the gradient of a grayscale image
+/// measured by a pair of [`GradientKernels`](GradientKernels).
+///
+/// The kernels are applied to luma values from 0.0 to 1.0, and pixels outside
+/// of the image take the value of the nearest edge pixel.
+pub fn gradient(
+    img: &image::DynamicImage,
+    kernels: &GradientKernels,
+) -> Result<Gradient, SkeletonizeError> {
+    let luma_img = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::SobelLuma,
+    ))?;
+
+    Ok(float_gradient(&luma_to_float(luma_img), kernels))
+}
+
+/// Calculate the gradient of an image which has already been converted to
+/// floating point.
+pub(super) fn float_gradient(luma: &FloatImage, kernels: &GradientKernels) -> Gradient {
+    let gradient_x = convolve(luma, &kernels.x, kernels.size);
+    let gradient_y = convolve(luma, &kernels.y, kernels.size);
+    let (width, height) = luma.dimensions();
+
+    Gradient {
+        magnitude: FloatImage::from_fn(width, height, |x, y| {
+            image::Luma([gradient_x[(x, y)][0].hypot(gradient_y[(x, y)][0])])
+        }),
+        angle: FloatImage::from_fn(width, height, |x, y| {
+            image::Luma([gradient_y[(x, y)][0].atan2(gradient_x[(x, y)][0])])
+        }),
+    }
+}
+
+/// Detect edges in a grayscale image from the magnitude of the gradient
+/// measured by a pair of [`GradientKernels`](GradientKernels).
+///
+/// The kernels are applied to luma values from 0.0 to 1.0, and pixels outside
+/// of the image take the value of the nearest edge pixel.
+///
+/// `threshold` is an optional parameter which is used to binarize the image.
+/// Pixels below that `Luma` threshold will be converted to the background
+/// color. The range of the magnitude depends on the kernels, for example a
+/// step from black to white has a magnitude of 4.0 with the Sobel kernels.
+/// Without a threshold, magnitudes above 1.0 saturate at the foreground color.
+pub fn gradient_magnitude<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    kernels: &GradientKernels,
+    threshold: Option<f32>,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    let gradient = gradient(img, kernels)?;
+
+    Ok(magnitude_to_image::<F>(&gradient.magnitude, threshold))
+}
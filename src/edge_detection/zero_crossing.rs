@@ -0,0 +1,179 @@
+//! Zero-crossing edge detection with the Laplacian of Gaussian and the
+//! difference of Gaussians.
+
+use super::convolution::{convolve, gaussian_blur, luma_to_float, FloatImage};
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::ForegroundColor;
+
+/// Discrete Laplacian operator.
+#[rustfmt::skip]
+const LAPLACIAN: [f32; 9] = [
+    0.0, 1.0, 0.0,
+    1.0, -4.0, 1.0,
+    0.0, 1.0, 0.0,
+];
+
+/// Ratio between the standard deviations of the two Gaussians in
+/// [`difference_of_gaussians`], which closely approximates the Laplacian of
+/// Gaussian.
+const DOG_SIGMA_RATIO: f32 = 1.6;
+
+/// Detect edges in a grayscale image at the zero crossings of the Laplacian of
+/// a Gaussian with a standard deviation of `sigma` pixels. The result is a
+/// binary image where edge pixels have the foreground color and all other
+/// pixels have the background color.
+///
+/// Larger values of `sigma` ignore more noise and small details. A zero
+/// crossing is only an edge if the response changes by at least `threshold`
+/// across it. The response is scaled by `sigma` cubed so that a step between
+/// black and white changes by about 0.3 for any `sigma`. A `threshold` of 0.0
+/// keeps every zero crossing, thresholds around 0.05 remove most noise.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::edge_detection::{difference_of_gaussians, laplacian_of_gaussian};
+/// use skeletonize::foreground;
+///
+/// // A dark disk on a light background
+/// let luma = image::GrayImage::from_fn(32, 32, |x, y| {
+///     let (dx, dy) = (x as i32 - 16, y as i32 - 16);
+///     image::Luma([if dx * dx + dy * dy < 64 { 20 } else { 230 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let edges = laplacian_of_gaussian::<foreground::Black>(&img, 1.5, 0.1)?;
+/// let edges = edges.as_luma8().unwrap();
+///
+/// // The outline of the disk is found, but not its center or the background
+/// assert!((6..=10).any(|x| edges[(x, 16)][0] == 0));
+/// assert_eq!(edges[(16, 16)][0], 255);
+/// assert_eq!(edges[(1, 1)][0], 255);
+///
+/// // The difference of Gaussians finds the same outline
+/// let approx = difference_of_gaussians::<foreground::Black>(&img, 1.5, 0.1)?;
+/// let approx = approx.as_luma8().unwrap();
+/// assert!((6..=10).any(|x| approx[(x, 16)][0] == 0));
+/// assert_eq!(approx[(16, 16)][0], 255);
+///
+/// // A step with a middle column halfway between its sides, where the response
+/// // is exactly zero on the edge
+/// let luma = image::GrayImage::from_fn(16, 8, |x, _| {
+///     image::Luma([if x < 8 { 55 } else if x == 8 { 155 } else { 255 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let edges = laplacian_of_gaussian::<foreground::White>(&img, 1.5, 0.05)?;
+/// let approx = difference_of_gaussians::<foreground::White>(&img, 1.5, 0.05)?;
+/// for edges in [edges, approx] {
+///     for (x, y, p) in edges.as_luma8().unwrap().enumerate_pixels() {
+///         assert_eq!(p[0], if x == 8 { 255 } else { 0 }, "({}, {})", x, y);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ### Reference
+///
+/// Marr, D. & Hildreth, E. (1980). Theory of Edge Detection. Proceedings of
+/// the Royal Society of London. Series B. 207. 187-217.
+/// [DOI:10.1098/rspb.1980.0020](https://doi.org/10.1098/rspb.1980.0020)
+pub fn laplacian_of_gaussian<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    sigma: f32,
+    threshold: f32,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    let luma_img = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::ZeroCrossingLuma,
+    ))?;
+
+    let mut response = convolve(
+        &gaussian_blur(&luma_to_float(luma_img), sigma),
+        &LAPLACIAN,
+        3,
+    );
+    if sigma > 0.0 {
+        let scale = sigma.powi(3);
+        response.iter_mut().for_each(|r| *r *= scale);
+    }
+
+    Ok(zero_crossings::<F>(&response, threshold))
+}
+
+/// Detect edges in a grayscale image at the zero crossings of the difference
+/// between two Gaussians, an approximation of the
+/// [`laplacian_of_gaussian`](laplacian_of_gaussian) which only needs two
+/// blurs. The result is a binary image where edge pixels have the foreground
+/// color and all other pixels have the background color.
+///
+/// The narrower Gaussian has a standard deviation of `sigma` pixels and the
+/// wider Gaussian is 1.6 times larger. The difference is scaled to match the
+/// Laplacian of Gaussian, so the same `threshold` gives similar results. A
+/// `sigma` of 0.0 doesn't find any edges.
+pub fn difference_of_gaussians<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    sigma: f32,
+    threshold: f32,
+) -> Result<image::DynamicImage, SkeletonizeError> {
+    let luma_img = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::ZeroCrossingLuma,
+    ))?;
+    let luma = luma_to_float(luma_img);
+
+    let mut response = gaussian_blur(&luma, sigma * DOG_SIGMA_RATIO);
+    let narrow = gaussian_blur(&luma, sigma);
+    let scale = sigma / (DOG_SIGMA_RATIO - 1.0);
+    for (r, n) in response.iter_mut().zip(narrow.iter()) {
+        *r = (*r - n) * scale;
+    }
+
+    Ok(zero_crossings::<F>(&response, threshold))
+}
+
+/// Mark pixels where `response` changes sign with a change of at least
+/// `threshold`. Of the two pixels on either side of a crossing, the one closer
+/// to zero is marked so that edges stay thin. A pixel with a response of
+/// exactly zero is marked when the pixels on opposite sides of it have
+/// opposite signs.
+fn zero_crossings<F: ForegroundColor>(
+    response: &FloatImage,
+    threshold: f32,
+) -> image::DynamicImage {
+    let (width, height) = response.dimensions();
+    let mut edges = image::GrayImage::from_pixel(width, height, image::Luma([F::BACKGROUND_COLOR]));
+
+    let crosses = |p: f32, q: f32| {
+        ((p < 0.0 && q > 0.0) || (p > 0.0 && q < 0.0)) && (p - q).abs() >= threshold
+    };
+
+    for (x, y, p) in response.enumerate_pixels() {
+        let p = p[0];
+        let right = (x + 1 < width).then(|| (x + 1, y));
+        let below = (y + 1 < height).then(|| (x, y + 1));
+
+        for (nx, ny) in right.into_iter().chain(below) {
+            let q = response[(nx, ny)][0];
+
+            if crosses(p, q) {
+                let closer = if p.abs() <= q.abs() { (x, y) } else { (nx, ny) };
+                edges.put_pixel(closer.0, closer.1, image::Luma([!F::BACKGROUND_COLOR]));
+            }
+        }
+
+        // The response can be exactly zero on the edge, such as in the middle
+        // of a symmetric step, so neither neighbor changes sign against it
+        if p == 0.0 {
+            let horizontal =
+                x > 0 && x + 1 < width && crosses(response[(x - 1, y)][0], response[(x + 1, y)][0]);
+            let vertical = y > 0
+                && y + 1 < height
+                && crosses(response[(x, y - 1)][0], response[(x, y + 1)][0]);
+
+            if horizontal || vertical {
+                edges.put_pixel(x, y, image::Luma([!F::BACKGROUND_COLOR]));
+            }
+        }
+    }
+
+    image::DynamicImage::ImageLuma8(edges)
+}
@@ -7,6 +7,8 @@ pub enum SkeletonizeError {
     LumaConversion(LumaConversionErrorKind),
     /// The edge thinning algorithm reached the maximum amount of iterations.
     MaxThinningIterations,
+    /// A convolution kernel had an even size or the wrong number of values.
+    InvalidKernel,
 }
 
 /// Errors that occur when attempting to convert an image to grayscale.
@@ -14,14 +16,45 @@ pub enum SkeletonizeError {
 pub enum LumaConversionErrorKind {
     /// Error converting an image into grayscale in an edge thinning algorithm.
     ImageThinningLuma,
-    /// Error converting an image to grayscale in an edge detection function.
+    /// Error converting an image to grayscale in a gradient edge detection
+    /// function, such as `sobel` or `gradient`.
     SobelLuma,
     /// Error converting an image into a mutable grayscale image view in an edge
     /// detection function.
+    #[deprecated(note = "no longer returned by any edge detection function")]
     SobelMutableLuma,
     /// Error converting an image into a mutable grayscale image view for
     /// thresholding.
     ThresholdMutableLuma,
+    /// Error converting an image into grayscale to create a binary image.
+    BinaryImageLuma,
+    /// Error converting an image to grayscale for adaptive thresholding.
+    AdaptiveThresholdLuma,
+    /// Error converting an image to grayscale for the medial axis transform.
+    MedialAxisLuma,
+    /// Error converting an image to grayscale for a distance transform.
+    DistanceTransformLuma,
+    /// Error converting an image into a mutable grayscale image view for
+    /// pruning.
+    PruningMutableLuma,
+    /// Error converting an image to grayscale for finding endpoints and
+    /// junctions.
+    FeatureDetectionLuma,
+    /// Error converting an image to grayscale for building a skeleton graph.
+    SkeletonGraphLuma,
+    /// Error converting an image to grayscale for connected component
+    /// labeling.
+    ComponentLabelingLuma,
+    /// Error converting an image into a mutable grayscale image view for
+    /// removing small objects or filling small holes.
+    SmallComponentsMutableLuma,
+    /// Error converting an image to grayscale for Canny edge detection.
+    CannyLuma,
+    /// Error converting an image to grayscale for compass edge detection.
+    CompassLuma,
+    /// Error converting an image to grayscale for zero-crossing edge
+    /// detection.
+    ZeroCrossingLuma,
 }
 
 impl core::fmt::Display for LumaConversionErrorKind {
@@ -31,6 +64,7 @@ impl core::fmt::Display for LumaConversionErrorKind {
                 write!(f, "Could not create a grayscale image in image thinning")
             }
             Self::SobelLuma => write!(f, "Could not create a grayscale image in edge detection"),
+            #[allow(deprecated)]
             Self::SobelMutableLuma => write!(
                 f,
                 "Could not create a mutable grayscale image view in edge detection"
@@ -39,6 +73,53 @@ impl core::fmt::Display for LumaConversionErrorKind {
                 f,
                 "Could not create a mutable grayscale image view for thresholding"
             ),
+            Self::BinaryImageLuma => {
+                write!(f, "Could not create a grayscale image for a binary image")
+            }
+            Self::AdaptiveThresholdLuma => write!(
+                f,
+                "Could not create a grayscale image for adaptive thresholding"
+            ),
+            Self::MedialAxisLuma => write!(
+                f,
+                "Could not create a grayscale image for the medial axis transform"
+            ),
+            Self::DistanceTransformLuma => write!(
+                f,
+                "Could not create a grayscale image for a distance transform"
+            ),
+            Self::PruningMutableLuma => write!(
+                f,
+                "Could not create a mutable grayscale image view for pruning"
+            ),
+            Self::FeatureDetectionLuma => write!(
+                f,
+                "Could not create a grayscale image for finding endpoints and junctions"
+            ),
+            Self::SkeletonGraphLuma => write!(
+                f,
+                "Could not create a grayscale image for building a skeleton graph"
+            ),
+            Self::ComponentLabelingLuma => write!(
+                f,
+                "Could not create a grayscale image for connected component labeling"
+            ),
+            Self::SmallComponentsMutableLuma => write!(
+                f,
+                "Could not create a mutable grayscale image view for removing small components"
+            ),
+            Self::CannyLuma => write!(
+                f,
+                "Could not create a grayscale image for Canny edge detection"
+            ),
+            Self::CompassLuma => write!(
+                f,
+                "Could not create a grayscale image for compass edge detection"
+            ),
+            Self::ZeroCrossingLuma => write!(
+                f,
+                "Could not create a grayscale image for zero-crossing edge detection"
+            ),
         }
     }
 }
@@ -50,6 +131,7 @@ impl core::fmt::Display for SkeletonizeError {
             Self::MaxThinningIterations => {
                 write!(f, "Maximum iteration count reached in thinning algorithm")
             }
+            Self::InvalidKernel => write!(f, "Kernel size must be odd and match its values"),
         }
     }
 }
@@ -57,7 +139,7 @@ impl core::fmt::Display for SkeletonizeError {
 impl std::error::Error for SkeletonizeError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Self::LumaConversion(_) | Self::MaxThinningIterations => None,
+            Self::LumaConversion(_) | Self::MaxThinningIterations | Self::InvalidKernel => None,
         }
     }
 }
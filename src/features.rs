@@ -0,0 +1,90 @@
+//! Detection of endpoints and junctions in thinned images.
+//!
+//! Pixels are classified by their
+//! [crossing number](crate::neighbors::NeighborInfo::crossing_number), the
+//! number of separate groups of foreground pixels around them. Skeletons from
+//! [`thin_image_edges`](crate::thin_image_edges) are one pixel wide, so each
+//! group is the start of a different branch.
+
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::neighbors::get_neighbor_info;
+use crate::ForegroundColor;
+
+/// A pixel where three or more branches of a skeleton meet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Junction {
+    /// Horizontal position of the junction.
+    pub x: u32,
+    /// Vertical position of the junction.
+    pub y: u32,
+    /// The number of branches which meet at the junction.
+    pub degree: u8,
+}
+
+/// The endpoints, junctions, and isolated points of a skeleton, each in
+/// row-major order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SkeletonFeatures {
+    /// Pixels at the end of a line, which have a crossing number of `1`.
+    pub endpoints: Vec<(u32, u32)>,
+    /// Pixels where lines branch, which have a crossing number of `3` or more.
+    pub junctions: Vec<Junction>,
+    /// Pixels without any foreground neighbors.
+    pub isolated: Vec<(u32, u32)>,
+}
+
+/// Find the endpoints, junctions, and isolated points of a thinned image, where
+/// every pixel which isn't the background color of `F` is part of the
+/// skeleton.
+///
+/// Where thick lines cross, thinning can leave a small cluster of pixels and
+/// more than one of them may be reported as a junction.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::features::{find_features, Junction};
+/// use skeletonize::foreground;
+///
+/// // A `T` and a single pixel, in black on white
+/// let luma = image::GrayImage::from_fn(9, 7, |x, y| {
+///     let t = (y == 1 && (1..8).contains(&x)) || (x == 4 && (2..6).contains(&y));
+///     image::Luma([if t || (x, y) == (7, 5) { 0 } else { 255 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let features = find_features::<foreground::Black>(&img)?;
+/// assert_eq!(features.endpoints, vec![(1, 1), (7, 1), (4, 5)]);
+/// assert_eq!(features.junctions, vec![Junction { x: 4, y: 1, degree: 3 }]);
+/// assert_eq!(features.isolated, vec![(7, 5)]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_features<F: ForegroundColor>(
+    img: &image::DynamicImage,
+) -> Result<SkeletonFeatures, SkeletonizeError> {
+    let luma = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::FeatureDetectionLuma,
+    ))?;
+    let (width, height) = luma.dimensions();
+    let mut features = SkeletonFeatures::default();
+
+    for (x, y, p) in luma.enumerate_pixels() {
+        if p[0] == F::BACKGROUND_COLOR {
+            continue;
+        }
+
+        let info = get_neighbor_info::<F>(luma, width, height, x, y);
+        if info.filled == 0 {
+            features.isolated.push((x, y));
+            continue;
+        }
+
+        match info.crossing_number() {
+            1 => features.endpoints.push((x, y)),
+            degree @ 3.. => features.junctions.push(Junction { x, y, degree }),
+            _ => {}
+        }
+    }
+
+    Ok(features)
+}
@@ -0,0 +1,377 @@
+//! Conversion of thinned images into graphs of branches.
+//!
+//! The nodes of a [`SkeletonGraph`](SkeletonGraph) are the endpoints and
+//! junctions of a skeleton, and its branches are the lines of pixels which
+//! connect them. Each branch keeps its pixels in order, so the graph can be
+//! used to follow roads, vessels, or strokes through an image.
+//!
+//! Pixels are linked to their 8 neighbors, except for diagonal neighbors which
+//! are already connected through a shared orthogonal neighbor. This keeps
+//! staircase steps of diagonal lines from looking like junctions.
+
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::neighbors::RING_OFFSETS;
+use crate::{BinaryImage, ForegroundColor};
+
+/// The type of a [`Node`](Node) in a skeleton graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    /// The end of a line, with one branch.
+    Endpoint,
+    /// A place where three or more branches meet.
+    Junction,
+    /// A pixel without any neighbors or branches.
+    Isolated,
+    /// The first pixel of a closed loop without any endpoints or junctions,
+    /// such as a thinned `O`. The loop is a branch which starts and ends at
+    /// this node.
+    Loop,
+}
+
+/// An endpoint, junction, isolated point, or loop of a skeleton.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    /// Horizontal position of the node.
+    pub x: u32,
+    /// Vertical position of the node.
+    pub y: u32,
+    /// The type of the node.
+    pub kind: NodeKind,
+    /// The pixels of the node in row-major order. Junctions where thick lines
+    /// cross can have several pixels, and `(x, y)` is the pixel closest to
+    /// their center.
+    pub pixels: Vec<(u32, u32)>,
+}
+
+/// A line of pixels connecting two nodes of a skeleton graph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Branch {
+    /// Index of the node where the branch starts.
+    pub start: usize,
+    /// Index of the node where the branch ends, which is the same as `start`
+    /// for loops.
+    pub end: usize,
+    /// The pixels of the branch in order from `start` to `end`, including a
+    /// pixel of each node. The first pixel is repeated at the end of loops.
+    pub pixels: Vec<(u32, u32)>,
+    /// The length of the path through the pixels, where diagonal steps have a
+    /// length of `√2`.
+    pub length: f32,
+}
+
+/// A graph of the nodes and branches of a skeleton, created by
+/// [`skeleton_graph`](skeleton_graph).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkeletonGraph {
+    nodes: Vec<Node>,
+    branches: Vec<Branch>,
+    incident: Vec<Vec<usize>>,
+}
+
+impl SkeletonGraph {
+    /// The nodes of the graph. Endpoints, junctions, and isolated points are in
+    /// row-major order, followed by loops.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// The branches of the graph.
+    pub fn branches(&self) -> &[Branch] {
+        &self.branches
+    }
+
+    /// The indices of the branches which start or end at `node`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` isn't the index of a node.
+    pub fn node_branches(&self, node: usize) -> &[usize] {
+        &self.incident[node]
+    }
+
+    /// The indices of the nodes which are connected to `node` by a branch, once
+    /// for each branch. A loop connects `node` to itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` isn't the index of a node.
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.incident[node].iter().map(move |&b| {
+            let branch = &self.branches[b];
+            if branch.start == node {
+                branch.end
+            } else {
+                branch.start
+            }
+        })
+    }
+}
+
+/// Convert a thinned image into a graph of its endpoints, junctions, and the
+/// branches between them, where every pixel which isn't the background color
+/// of `F` is part of the skeleton.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::foreground;
+/// use skeletonize::graph::{skeleton_graph, NodeKind};
+///
+/// // A `T` next to an outlined rectangle
+/// let luma = image::GrayImage::from_fn(20, 8, |x, y| {
+///     let t = (y == 1 && (10..17).contains(&x)) || (x == 13 && (2..6).contains(&y));
+///     let rectangle = ((2..9).contains(&x) && (y == 2 || y == 6))
+///         || ((x == 2 || x == 8) && (2..7).contains(&y));
+///     image::Luma([if t || rectangle { 255 } else { 0 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let graph = skeleton_graph::<foreground::White>(&img)?;
+///
+/// // The junction of the `T` connects to its three endpoints
+/// let junction = graph.nodes().iter().position(|n| n.kind == NodeKind::Junction).unwrap();
+/// assert_eq!((graph.nodes()[junction].x, graph.nodes()[junction].y), (13, 1));
+/// assert_eq!(graph.neighbors(junction).count(), 3);
+/// for n in graph.neighbors(junction) {
+///     assert_eq!(graph.nodes()[n].kind, NodeKind::Endpoint);
+/// }
+///
+/// // The rectangle is a single branch which starts and ends at the same node
+/// let outline = graph.nodes().iter().position(|n| n.kind == NodeKind::Loop).unwrap();
+/// let branch = &graph.branches()[graph.node_branches(outline)[0]];
+/// assert_eq!((branch.start, branch.end), (outline, outline));
+/// assert_eq!(branch.length, 20.0);
+/// # Ok(())
+/// # }
+/// ```
+pub fn skeleton_graph<F: ForegroundColor>(
+    img: &image::DynamicImage,
+) -> Result<SkeletonGraph, SkeletonizeError> {
+    let luma = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::SkeletonGraphLuma,
+    ))?;
+    let skeleton = BinaryImage::from_luma::<F>(luma);
+    let (width, height) = skeleton.dimensions();
+    let index = |(x, y): (u32, u32)| y as usize * width as usize + x as usize;
+
+    let pixels = || (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)));
+    let links: Vec<u8> = pixels().map(|(x, y)| links(&skeleton, x, y)).collect();
+
+    // Every pixel which doesn't continue a line is part of a node, and
+    // neighboring junction pixels are part of the same node
+    let mut node_of = vec![usize::MAX; links.len()];
+    let mut nodes = Vec::new();
+    for pos in pixels() {
+        let degree = links[index(pos)].count_ones();
+        if !skeleton.get(pos.0, pos.1) || degree == 2 || node_of[index(pos)] != usize::MAX {
+            continue;
+        }
+
+        let kind = match degree {
+            0 => NodeKind::Isolated,
+            1 => NodeKind::Endpoint,
+            _ => NodeKind::Junction,
+        };
+
+        let mut cluster = vec![pos];
+        node_of[index(pos)] = nodes.len();
+        let mut next = 0;
+        while kind == NodeKind::Junction && next < cluster.len() {
+            let current = cluster[next];
+            next += 1;
+            for neighbor in linked(current, links[index(current)]) {
+                let i = index(neighbor);
+                if links[i].count_ones() > 2 && node_of[i] == usize::MAX {
+                    node_of[i] = nodes.len();
+                    cluster.push(neighbor);
+                }
+            }
+        }
+
+        nodes.push(node(kind, cluster));
+    }
+
+    let mut tracer = Tracer {
+        width,
+        links,
+        node_of,
+        used: vec![0; width as usize * height as usize],
+        branches: Vec::new(),
+    };
+
+    for n in 0..nodes.len() {
+        for pixel in nodes[n].pixels.clone() {
+            tracer.trace_from(pixel, &mut nodes);
+        }
+    }
+
+    // Anything left over is a loop without any endpoints or junctions
+    for pos in pixels() {
+        let i = index(pos);
+        if tracer.links[i] != 0 && tracer.used[i] == 0 && tracer.node_of[i] == usize::MAX {
+            tracer.node_of[i] = nodes.len();
+            nodes.push(node(NodeKind::Loop, vec![pos]));
+            tracer.trace_from(pos, &mut nodes);
+        }
+    }
+
+    let mut incident = vec![Vec::new(); nodes.len()];
+    for (b, branch) in tracer.branches.iter().enumerate() {
+        incident[branch.start].push(b);
+        if branch.end != branch.start {
+            incident[branch.end].push(b);
+        }
+    }
+
+    Ok(SkeletonGraph {
+        nodes,
+        branches: tracer.branches,
+        incident,
+    })
+}
+
+/// Follows branches through the links between pixels, using each link once.
+struct Tracer {
+    width: u32,
+    links: Vec<u8>,
+    node_of: Vec<usize>,
+    used: Vec<u8>,
+    branches: Vec<Branch>,
+}
+
+impl Tracer {
+    fn index(&self, (x, y): (u32, u32)) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Mark the link from `pos` in `direction` and the link back as used, and
+    /// return the linked pixel.
+    fn take_link(&mut self, pos: (u32, u32), direction: usize) -> (u32, u32) {
+        let next = step(pos, direction);
+        let (from, to) = (self.index(pos), self.index(next));
+        self.used[from] |= 1 << direction;
+        self.used[to] |= 1 << ((direction + 4) % 8);
+
+        next
+    }
+
+    /// Trace every unused link from the node pixel `start` to the node at the
+    /// other end of the branch.
+    fn trace_from(&mut self, start: (u32, u32), nodes: &mut [Node]) {
+        let start_node = self.node_of[self.index(start)];
+
+        for direction in 0..8 {
+            let i = self.index(start);
+            if self.links[i] & !self.used[i] & 1 << direction == 0 {
+                continue;
+            }
+
+            let mut pixels = vec![start];
+            let mut current = self.take_link(start, direction);
+            pixels.push(current);
+
+            while self.node_of[self.index(current)] == usize::MAX {
+                let i = self.index(current);
+                let remaining = self.links[i] & !self.used[i];
+                current = self.take_link(current, remaining.trailing_zeros() as usize);
+                pixels.push(current);
+            }
+
+            let end_node = self.node_of[self.index(current)];
+            match pixels.len() {
+                // Links between pixels of the same junction aren't branches
+                2 if end_node == start_node => {}
+                // A single pixel between two pixels of a junction belongs to
+                // the junction
+                3 if end_node == start_node && nodes[start_node].kind == NodeKind::Junction => {
+                    let middle = pixels[1];
+                    let i = self.index(middle);
+                    self.node_of[i] = start_node;
+                    let node_pixels = &mut nodes[start_node].pixels;
+                    node_pixels.push(middle);
+                    node_pixels.sort_unstable_by_key(|&(x, y)| (y, x));
+                }
+                _ => self.branches.push(Branch {
+                    start: start_node,
+                    end: end_node,
+                    length: path_length(&pixels),
+                    pixels,
+                }),
+            }
+        }
+    }
+}
+
+/// Create a node from its pixels, positioned at the pixel closest to their
+/// center.
+fn node(kind: NodeKind, mut pixels: Vec<(u32, u32)>) -> Node {
+    pixels.sort_unstable_by_key(|&(x, y)| (y, x));
+
+    let count = pixels.len() as f32;
+    let cx = pixels.iter().map(|&(x, _)| x as f32).sum::<f32>() / count;
+    let cy = pixels.iter().map(|&(_, y)| y as f32).sum::<f32>() / count;
+    let distance = |&&(x, y): &&(u32, u32)| (x as f32 - cx).powi(2) + (y as f32 - cy).powi(2);
+    let &(x, y) = pixels
+        .iter()
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .expect("nodes have at least one pixel");
+
+    Node { x, y, kind, pixels }
+}
+
+/// Pack the directions in which `(x, y)` is linked to a neighbor into a `u8`,
+/// in the same order as [`RING_OFFSETS`](RING_OFFSETS).
+fn links(img: &BinaryImage, x: u32, y: u32) -> u8 {
+    if !img.get(x, y) {
+        return 0;
+    }
+
+    let (width, height) = img.dimensions();
+    let filled = |dx: i64, dy: i64| {
+        let (nx, ny) = (i64::from(x) + dx, i64::from(y) + dy);
+        nx >= 0
+            && ny >= 0
+            && nx < i64::from(width)
+            && ny < i64::from(height)
+            && img.get(nx as u32, ny as u32)
+    };
+
+    RING_OFFSETS
+        .iter()
+        .enumerate()
+        .fold(0, |links, (direction, &(dx, dy))| {
+            let diagonal = dx != 0 && dy != 0;
+            if filled(dx, dy) && !(diagonal && (filled(dx, 0) || filled(0, dy))) {
+                links | 1 << direction
+            } else {
+                links
+            }
+        })
+}
+
+/// Iterate over the pixels linked to `pos`.
+fn linked(pos: (u32, u32), links: u8) -> impl Iterator<Item = (u32, u32)> {
+    (0..8)
+        .filter(move |direction| links & 1 << direction != 0)
+        .map(move |direction| step(pos, direction))
+}
+
+/// The neighbor of `pos` in `direction`, which must be inside of the image.
+fn step((x, y): (u32, u32), direction: usize) -> (u32, u32) {
+    let (dx, dy) = RING_OFFSETS[direction];
+
+    ((i64::from(x) + dx) as u32, (i64::from(y) + dy) as u32)
+}
+
+/// The length of a path of neighboring pixels.
+fn path_length(pixels: &[(u32, u32)]) -> f32 {
+    pixels
+        .windows(2)
+        .map(|pair| {
+            if pair[0].0 != pair[1].0 && pair[0].1 != pair[1].1 {
+                core::f32::consts::SQRT_2
+            } else {
+                1.0
+            }
+        })
+        .sum()
+}
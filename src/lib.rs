@@ -5,8 +5,8 @@
 //! lines present are one pixel wide, resembling a "skeleton" of the original
 //! pattern.
 //!
-//! The thinning algorithms are based on the papers *Zhang & Suen, 1984* and
-//! *Chen & Hsu, 1988*. See [Reference](#reference).
+//! The thinning algorithms are based on the papers *Zhang & Suen, 1984*,
+//! *Chen & Hsu, 1988*, and *Guo & Hall, 1989*. See [Reference](#reference).
 //!
 //! ## Usage
 //!
@@ -15,7 +15,7 @@
 //! before thinning the image.
 //!
 //! The generic [`ForegroundColor`](crate::ForegroundColor) parameter on
-//! [`edge_detection::sobel`][sobel], [`edge_detection::sobel4`][sobel4], and
+//! [`edge_detection::sobel`][sobel] and
 //! [`thin_image_edges`](crate::thin_image_edges) specifies what foreground and
 //! background colors the resulting
 //! [`thin_image_edges`](crate::thin_image_edges) image will produce. The
@@ -25,7 +25,6 @@
 //! detection function in combination with the thinning function.
 //!
 //! [sobel]: crate::edge_detection::sobel
-//! [sobel4]: crate::edge_detection::sobel4
 //!
 //! An example program can be viewed at `/examples/skeletonize.rs`.
 //!
@@ -48,7 +47,7 @@
 //!
 //! If this produces poor results and/or takes a long time to run:
 //! - the incorrect foreground color may have been chosen - try using the
-//! opposite color, or
+//!   opposite color, or
 //! - the image may not be binary and needs to be thresholded.
 //!
 //! #### Edge detection
@@ -59,7 +58,7 @@
 //!
 //! ```
 //! # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
-//! use skeletonize::edge_detection::sobel4;
+//! use skeletonize::edge_detection::sobel;
 //! use skeletonize::{foreground, thin_image_edges, MarkingMethod};
 //!
 //! # let image_buffer = image::ImageBuffer::from_pixel(2, 2, image::Rgb([255, 255, 255]));
@@ -67,12 +66,15 @@
 //! let method = MarkingMethod::Modified;
 //! let threshold = Some(0.1);
 //!
-//! let mut filtered = sobel4::<foreground::White>(&img, threshold)?;
+//! let mut filtered = sobel::<foreground::White>(&img, threshold)?;
 //! thin_image_edges::<foreground::White>(&mut filtered, method, None)?;
 //! # Ok(())
 //! # }
 //! ```
 //!
+//! [`edge_detection::canny`](crate::edge_detection::canny) produces edges which
+//! are already close to one pixel wide and often need little thinning.
+//!
 //! #### Thresholding
 //!
 //! Threshold the image before thinning, e.g., cleaning up a grayscale image.
@@ -92,6 +94,49 @@
 //! # }
 //! ```
 //!
+//! [`threshold`](crate::threshold) always produces black lines on a white
+//! background. Use [`threshold_fg`](crate::threshold_fg) to binarize an image
+//! for a specific foreground color, such as thinning dark lines as a white
+//! foreground.
+//!
+//! The threshold can also be chosen automatically from the image histogram with
+//! [`threshold_otsu`](crate::threshold_otsu). Unevenly lit images can be
+//! binarized with a threshold that varies across the image using
+//! [`adaptive::adaptive_threshold`](crate::adaptive::adaptive_threshold).
+//! Specks of noise left by thresholding can be cleaned up before thinning with
+//! [`components::remove_small_objects`](crate::components::remove_small_objects)
+//! and [`components::fill_small_holes`](crate::components::fill_small_holes).
+//!
+//! #### After thinning
+//!
+//! Short spurs left by noise along the edges of lines can be removed from a
+//! thinned image with [`pruning::prune`](crate::pruning::prune). The endpoints
+//! and junctions of the lines can be found with
+//! [`features::find_features`](crate::features::find_features), and
+//! [`graph::skeleton_graph`](crate::graph::skeleton_graph) connects them into a
+//! graph of branches with their pixels in order.
+//! [`vectorize::polylines`](crate::vectorize::polylines) traces the branches
+//! into polylines which can be simplified to fewer points, and the `svg`
+//! feature writes them as an SVG image.
+//!
+//! [`medial_axis::medial_axis`](crate::medial_axis::medial_axis) thins an image
+//! while keeping the distance from each skeleton pixel to the background, which
+//! measures the width of the strokes.
+//! [`distance::distance_transform`](crate::distance::distance_transform)
+//! measures the distance to the background from every pixel.
+//!
+//! Separate strokes and objects can be counted and measured before or after
+//! thinning with
+//! [`components::label_components`](crate::components::label_components).
+//!
+//! ## Features
+//!
+//! - `rayon` - Mark the pixels to remove in each
+//!   [`thin_image_edges`](crate::thin_image_edges) pass in parallel. The output
+//!   is the same as without the feature.
+//! - `svg` - Write polylines traced from skeletons as SVG images with the `svg`
+//!   module.
+//!
 //! ## Reference
 //!
 //! Zhang, T. Y. & Suen, C. Y. (1984). A fast parallel algorithm for thinning
@@ -102,15 +147,32 @@
 //! algorithm for thinning digital patterns. Pattern Recognition Letters. 7.
 //! 99-106.
 //! [DOI:10.1016/0167-8655(88)90124-9](https://doi.org/10.1016/0167-8655(88)90124-9)
+//!
+//! Guo, Zicheng & Hall, Richard W. (1989). Parallel thinning with
+//! two-subiteration algorithms. Commun. ACM 32, 3 (March 1989), 359–373.
+//! [DOI:10.1145/62065.62074](https://doi.org/10.1145/62065.62074)
 #![warn(missing_docs, rust_2018_idioms, unsafe_code)]
 
+pub mod adaptive;
+mod binary_image;
+pub mod components;
+pub mod distance;
 pub mod edge_detection;
 pub mod error;
+pub mod features;
+pub mod graph;
+mod lookup;
+pub mod medial_axis;
 pub mod neighbors;
+pub mod pruning;
+#[cfg(feature = "svg")]
+pub mod svg;
 mod thinning;
+pub mod vectorize;
 
+pub use binary_image::BinaryImage;
 use error::{LumaConversionErrorKind, SkeletonizeError};
-pub use thinning::thin_image_edges;
+pub use thinning::{thin_binary_image_edges, thin_image_edges};
 
 /// Represents the color of the foreground or features in a binary image. For
 /// example, white text on a black background has a white foreground color and
@@ -173,7 +235,11 @@ impl Edge {
 /// modified fast parallel algorithm for thinning digital patterns. Pattern
 /// Recognition Letters. 7. 99-106.
 /// [DOI:10.1016/0167-8655(88)90124-9](https://doi.org/10.1016/0167-8655(88)90124-9)
-#[derive(Clone, Copy, Debug, PartialEq)]
+///
+/// <span id="guohall"></span>Guo, Zicheng & Hall, Richard W. (1989). Parallel
+/// thinning with two-subiteration algorithms. Commun. ACM 32, 3 (March 1989),
+/// 359–373. [DOI:10.1145/62065.62074](https://doi.org/10.1145/62065.62074)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum MarkingMethod {
     /// An algorithm based on `Zhang and Suen, 1984`.
     ///
@@ -184,18 +250,94 @@ pub enum MarkingMethod {
     /// weaknesses with generally thinner lines and better line connectivity.
     ///
     /// See [MarkingMethod](crate::MarkingMethod#modified) for reference.
+    #[default]
     Modified,
-}
-
-impl Default for MarkingMethod {
-    fn default() -> Self {
-        Self::Modified
-    }
+    /// An algorithm based on `Guo and Hall, 1989`. Pixels are removed based on
+    /// the number of 8-connected components in their neighborhood, which tends
+    /// to preserve diagonal lines better than `Standard` and `Modified`.
+    ///
+    /// See [MarkingMethod](crate::MarkingMethod#guohall) for reference.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+    /// use skeletonize::{foreground, thin_image_edges, MarkingMethod};
+    ///
+    /// // A diagonal stroke three pixels wide on a white background
+    /// let stroke = image::GrayImage::from_fn(16, 16, |x, y| {
+    ///     if (x as i32 - y as i32).abs() <= 1 && (2..14).contains(&x) {
+    ///         image::Luma([0])
+    ///     } else {
+    ///         image::Luma([255])
+    ///     }
+    /// });
+    /// let count = |img: &image::GrayImage| img.pixels().filter(|p| p[0] == 0).count();
+    ///
+    /// let mut skeletons = Vec::new();
+    /// for method in [
+    ///     MarkingMethod::Standard,
+    ///     MarkingMethod::Modified,
+    ///     MarkingMethod::GuoHall,
+    /// ] {
+    ///     let mut img = image::DynamicImage::ImageLuma8(stroke.clone());
+    ///     thin_image_edges::<foreground::Black>(&mut img, method, None)?;
+    ///     skeletons.push(img.into_luma8());
+    /// }
+    ///
+    /// // Every method thins the stroke without erasing it
+    /// assert!(skeletons
+    ///     .iter()
+    ///     .all(|img| (1..count(&stroke)).contains(&count(img))));
+    ///
+    /// // Only Guo-Hall keeps the pixels at both ends of the stroke
+    /// let ends = |img: &image::GrayImage| img[(2, 1)][0] == 0 && img[(13, 14)][0] == 0;
+    /// assert!(!ends(&skeletons[0]) && !ends(&skeletons[1]));
+    /// assert!(ends(&skeletons[2]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    GuoHall,
 }
 
 /// Create a binary image where values below `threshold` become black and above
 /// become white. `threshold` ranges from 0.0 to 1.0.
 pub fn threshold(img: &mut image::DynamicImage, threshold: f32) -> Result<(), SkeletonizeError> {
+    threshold_fg::<foreground::Black>(img, threshold, false)
+}
+
+/// Create a binary image where values below `threshold` become the foreground
+/// color and above become the background color. `threshold` ranges from 0.0 to
+/// 1.0.
+///
+/// If `invert` is `true`, values above `threshold` become the foreground color
+/// instead, such as when the lines in the image are lighter than their
+/// surroundings.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::{foreground, thin_image_edges, threshold_fg, MarkingMethod};
+///
+/// // A dark line on a light background
+/// let luma = image::GrayImage::from_fn(8, 8, |_, y| {
+///     image::Luma([if (3..5).contains(&y) { 30 } else { 220 }])
+/// });
+/// let mut img = image::DynamicImage::ImageLuma8(luma);
+///
+/// // Thin the dark line as a white foreground without inverting the image
+/// threshold_fg::<foreground::White>(&mut img, 0.5, false)?;
+/// assert_eq!(img.as_luma8().unwrap()[(4, 4)][0], 255);
+/// assert_eq!(img.as_luma8().unwrap()[(4, 0)][0], 0);
+///
+/// thin_image_edges::<foreground::White>(&mut img, MarkingMethod::Modified, None)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn threshold_fg<F: ForegroundColor>(
+    img: &mut image::DynamicImage,
+    threshold: f32,
+    invert: bool,
+) -> Result<(), SkeletonizeError> {
+    let level = (threshold * 255.0).round() as u8;
+
     for pix in img
         .as_mut_luma8()
         .ok_or(SkeletonizeError::LumaConversion(
@@ -203,12 +345,98 @@ pub fn threshold(img: &mut image::DynamicImage, threshold: f32) -> Result<(), Sk
         ))?
         .iter_mut()
     {
-        *pix = if *pix < (threshold * 255.0).round() as u8 {
-            0
+        *pix = if (*pix < level) != invert {
+            !F::BACKGROUND_COLOR
         } else {
-            255
+            F::BACKGROUND_COLOR
         };
     }
 
     Ok(())
 }
+
+/// Calculate a threshold for [`threshold`](crate::threshold) from the luma
+/// histogram of `img` using Otsu's method, which picks the level that best
+/// separates the image into dark and light pixels. The result ranges from 0.0
+/// to 1.0.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// // Dark text around 40 on a light page around 210
+/// let luma = image::GrayImage::from_fn(8, 8, |x, y| {
+///     image::Luma([if x == y { 40 + x as u8 } else { 210 - y as u8 }])
+/// });
+/// let mut img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let level = skeletonize::threshold_otsu(&mut img)?;
+/// assert!(level > 40.0 / 255.0 && level < 210.0 / 255.0);
+///
+/// for (x, y, p) in img.as_luma8().unwrap().enumerate_pixels() {
+///     assert_eq!(p[0], if x == y { 0 } else { 255 });
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ### Reference
+///
+/// Otsu, Nobuyuki. (1979). A Threshold Selection Method from Gray-Level
+/// Histograms. IEEE Transactions on Systems, Man, and Cybernetics. 9. 62-66.
+/// [DOI:10.1109/TSMC.1979.4310076](https://doi.org/10.1109/TSMC.1979.4310076)
+pub fn otsu_threshold(img: &image::DynamicImage) -> f32 {
+    let mut histogram = [0u64; 256];
+    match img.as_luma8() {
+        Some(luma_img) => luma_img
+            .iter()
+            .for_each(|&p| histogram[usize::from(p)] += 1),
+        None => img
+            .to_luma8()
+            .iter()
+            .for_each(|&p| histogram[usize::from(p)] += 1),
+    }
+
+    let total = histogram.iter().sum::<u64>() as f64;
+    let total_sum = histogram
+        .iter()
+        .enumerate()
+        .map(|(level, &count)| level as f64 * count as f64)
+        .sum::<f64>();
+
+    // An image with a single gray level thresholds to white
+    let mut best_level = (total_sum / total.max(1.0)).round() as usize;
+    let mut best_variance = 0.0;
+    let mut background_count = 0.0;
+    let mut background_sum = 0.0;
+
+    for (level, &count) in histogram.iter().enumerate() {
+        background_count += count as f64;
+        background_sum += level as f64 * count as f64;
+        let foreground_count = total - background_count;
+
+        if background_count == 0.0 || foreground_count == 0.0 {
+            continue;
+        }
+
+        let mean_difference =
+            background_sum / background_count - (total_sum - background_sum) / foreground_count;
+        let variance = background_count * foreground_count * mean_difference * mean_difference;
+
+        if variance > best_variance {
+            best_variance = variance;
+            // Levels up to and including `level` become black
+            best_level = level + 1;
+        }
+    }
+
+    best_level as f32 / 255.0
+}
+
+/// Binarize `img` with [`threshold`](crate::threshold) at the level calculated
+/// by [`otsu_threshold`](crate::otsu_threshold). Returns the threshold that was
+/// used.
+pub fn threshold_otsu(img: &mut image::DynamicImage) -> Result<f32, SkeletonizeError> {
+    let level = otsu_threshold(img);
+    threshold(img, level)?;
+
+    Ok(level)
+}
@@ -0,0 +1,117 @@
+//! Pixel deletion lookup tables for the thinning marking methods.
+//!
+//! Each table has an entry for every arrangement of the 8 neighbors around a
+//! pixel, indexed by the bits returned from
+//! [`get_neighbor_bits`](crate::neighbors::get_neighbor_bits). The tables are
+//! evaluated at compile time so the thinning loop only performs one lookup per
+//! pixel.
+
+use crate::MarkingMethod;
+
+/// Deletion tables for each marking method, one table per subiteration.
+static TABLES: [[[bool; 256]; 2]; 3] = [
+    build_tables(MarkingMethod::Standard),
+    build_tables(MarkingMethod::Modified),
+    build_tables(MarkingMethod::GuoHall),
+];
+
+/// Return the deletion table for `method` during the first or second
+/// subiteration.
+pub(crate) fn deletion_table(method: MarkingMethod, phase_one: bool) -> &'static [bool; 256] {
+    let method = match method {
+        MarkingMethod::Standard => 0,
+        MarkingMethod::Modified => 1,
+        MarkingMethod::GuoHall => 2,
+    };
+
+    &TABLES[method][usize::from(!phase_one)]
+}
+
+const fn build_tables(method: MarkingMethod) -> [[bool; 256]; 2] {
+    let mut tables = [[false; 256]; 2];
+    let mut bits = 0;
+    while bits < 256 {
+        tables[0][bits] = is_removable(method, true, bits as u8);
+        tables[1][bits] = is_removable(method, false, bits as u8);
+        bits += 1;
+    }
+
+    tables
+}
+
+/// Whether a foreground pixel with the neighborhood `bits` is marked for
+/// removal. Bit `0` is the north neighbor `p2` and the bits continue clockwise
+/// to the north-west neighbor `p9`.
+#[allow(clippy::nonminimal_bool)]
+const fn is_removable(method: MarkingMethod, phase_one: bool, bits: u8) -> bool {
+    let [p2, p3, p4, p5, p6, p7, p8, p9] = [
+        bits & 1 != 0,
+        bits & 1 << 1 != 0,
+        bits & 1 << 2 != 0,
+        bits & 1 << 3 != 0,
+        bits & 1 << 4 != 0,
+        bits & 1 << 5 != 0,
+        bits & 1 << 6 != 0,
+        bits & 1 << 7 != 0,
+    ];
+    let filled = bits.count_ones();
+    // Number of times an edge transitions from empty to filled
+    let transitions = (!bits & bits.rotate_right(1)).count_ones();
+
+    match method {
+        MarkingMethod::Standard => {
+            // Zhang and Suen, 1984
+            if filled < 2 || filled > 6 || transitions != 1 {
+                return false;
+            }
+
+            if phase_one {
+                (!p2 || !p4 || !p6) && (!p4 || !p6 || !p8)
+            } else {
+                (!p2 || !p4 || !p8) && (!p2 || !p6 || !p8)
+            }
+        }
+        MarkingMethod::Modified => {
+            // Chen and Hsu, 1988
+            if filled < 2 || filled > 7 || !(transitions == 1 || transitions == 2) {
+                return false;
+            }
+
+            match (phase_one, transitions == 1) {
+                (true, true) => (!p2 || !p4 || !p6) && (!p4 || !p6 || !p8),
+                (true, false) => (p2 && p4 && !p6 && !p7 && !p8) || (p4 && p6 && !p2 && !p8 && !p9),
+                (false, true) => (!p2 || !p4 || !p8) && (!p2 || !p6 || !p8),
+                (false, false) => {
+                    (p2 && p8 && !p4 && !p5 && !p6) || (p6 && p8 && !p2 && !p3 && !p4)
+                }
+            }
+        }
+        MarkingMethod::GuoHall => {
+            // Guo and Hall, 1989
+
+            // Number of 8-connected components in the neighborhood
+            let connectivity = (!p2 && (p3 || p4)) as u8
+                + (!p4 && (p5 || p6)) as u8
+                + (!p6 && (p7 || p8)) as u8
+                + (!p8 && (p9 || p2)) as u8;
+
+            if connectivity != 1 {
+                return false;
+            }
+
+            let n1 = (p9 || p2) as u8 + (p3 || p4) as u8 + (p5 || p6) as u8 + (p7 || p8) as u8;
+            let n2 = (p2 || p3) as u8 + (p4 || p5) as u8 + (p6 || p7) as u8 + (p8 || p9) as u8;
+            let n = if n1 < n2 { n1 } else { n2 };
+
+            if n < 2 || n > 3 {
+                return false;
+            }
+
+            if phase_one {
+                !((p2 || p3 || !p5) && p4)
+            } else {
+                !((p6 || p7 || !p9) && p8)
+            }
+        }
+    }
+}
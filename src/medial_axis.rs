@@ -0,0 +1,261 @@
+//! Medial axis transform for measuring the thickness of shapes.
+//!
+//! The medial axis is a skeleton where each point keeps the radius of the
+//! largest disk centered on it which fits inside the foreground. The shape can
+//! be rebuilt from the disks of the points, and a stroke centered on a point
+//! is about `2 * radius - 1` pixels wide.
+//!
+//! Radii come from an exact Euclidean distance transform, where the radius of
+//! a pixel is its distance to the nearest background pixel. Pixels outside of
+//! the image aren't background.
+
+use std::collections::HashMap;
+
+use crate::distance::squared_euclidean;
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::neighbors::surrounding;
+use crate::{thin_binary_image_edges, BinaryImage, ForegroundColor, MarkingMethod};
+
+/// A point on the medial axis and the radius of its inscribed disk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MedialPoint {
+    /// Horizontal position of the point.
+    pub x: u32,
+    /// Vertical position of the point.
+    pub y: u32,
+    /// Distance from the point to the nearest background pixel. Every pixel
+    /// which is closer than `radius` to the point is foreground. The radius is
+    /// infinite if the image doesn't have any background pixels.
+    pub radius: f32,
+}
+
+/// The medial axis of an image, created by [`medial_axis`](medial_axis).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MedialAxis {
+    /// Width of the image.
+    pub width: u32,
+    /// Height of the image.
+    pub height: u32,
+    /// Points on the medial axis in row-major order.
+    pub points: Vec<MedialPoint>,
+}
+
+/// Calculate the medial axis of the foreground of a binary image, where every
+/// pixel which isn't the background color of `F` is foreground.
+///
+/// The axis starts from the skeleton left by thinning the image with `method`,
+/// which is connected and one pixel wide. Disks centered on the skeleton don't
+/// always reach into corners, so the centers of the largest disks covering the
+/// rest of the foreground are also added. These points are often single pixels
+/// which aren't connected to the thinned skeleton. The disks of all points
+/// cover the foreground exactly, so [`reconstruct`](reconstruct) rebuilds the
+/// original image.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::medial_axis::{medial_axis, reconstruct};
+/// use skeletonize::{foreground, MarkingMethod};
+///
+/// // A black bar which is 5 pixels tall
+/// let luma = image::GrayImage::from_fn(16, 9, |x, y| {
+///     image::Luma([if (2..14).contains(&x) && (2..7).contains(&y) { 0 } else { 255 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let axis = medial_axis::<foreground::Black>(&img, MarkingMethod::Modified)?;
+///
+/// // The axis runs along the middle row, 3 pixels from the background
+/// let middle = axis.points.iter().find(|p| (p.x, p.y) == (8, 4)).unwrap();
+/// assert_eq!(middle.radius, 3.0);
+/// assert!(axis.points.iter().all(|p| p.y == 4 || p.radius < 3.0));
+///
+/// assert_eq!(reconstruct::<foreground::Black>(&axis), img);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ### Reference
+///
+/// Blum, Harry. (1967). A Transformation for Extracting New Descriptors of
+/// Shape. Models for the Perception of Speech and Visual Form. 362-380.
+pub fn medial_axis<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    method: MarkingMethod,
+) -> Result<MedialAxis, SkeletonizeError> {
+    let luma = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::MedialAxisLuma,
+    ))?;
+    let (width, height) = luma.dimensions();
+    let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+
+    let foreground: Vec<bool> = luma.iter().map(|&p| p != F::BACKGROUND_COLOR).collect();
+    let squared: Vec<u32> = squared_euclidean(&foreground, width, height)
+        .into_iter()
+        .map(|d| if d.is_finite() { d as u32 } else { u32::MAX })
+        .collect();
+
+    let mut skeleton = BinaryImage::from_luma::<F>(luma);
+    thin_binary_image_edges(&mut skeleton, method, None)?;
+
+    let mut on_axis = vec![false; foreground.len()];
+    let mut covered = vec![false; foreground.len()];
+    for (x, y) in (0..height).flat_map(|y| (0..width).map(move |x| (x, y))) {
+        if skeleton.get(x, y) {
+            on_axis[index(x, y)] = true;
+            fill_disk(&mut covered, width, height, (x, y), squared[index(x, y)]);
+        }
+    }
+
+    // Follow disks which contain the disk of an uncovered pixel until reaching
+    // a disk that isn't contained in any of its neighbors' disks
+    let mut reaches = HashMap::new();
+    for (x, y) in (0..height).flat_map(|y| (0..width).map(move |x| (x, y))) {
+        if !foreground[index(x, y)] || covered[index(x, y)] {
+            continue;
+        }
+
+        let mut center = (x, y);
+        loop {
+            let squared_radius = squared[index(center.0, center.1)];
+            let reach = *reaches
+                .entry(squared_radius)
+                .or_insert_with(|| disk_reach(squared_radius));
+
+            let container = surrounding(center.0, center.1, width, height).find(|&(nx, ny)| {
+                let diagonal = nx != center.0 && ny != center.1;
+                reach[usize::from(diagonal)] < squared[index(nx, ny)]
+            });
+
+            match container {
+                Some(neighbor) => center = neighbor,
+                None => break,
+            }
+        }
+
+        on_axis[index(center.0, center.1)] = true;
+        let squared_radius = squared[index(center.0, center.1)];
+        fill_disk(&mut covered, width, height, center, squared_radius);
+    }
+
+    let points = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| on_axis[index(x, y)])
+        .map(|(x, y)| MedialPoint {
+            x,
+            y,
+            radius: match squared[index(x, y)] {
+                u32::MAX => f32::INFINITY,
+                squared_radius => (squared_radius as f32).sqrt(),
+            },
+        })
+        .collect();
+
+    Ok(MedialAxis {
+        width,
+        height,
+        points,
+    })
+}
+
+/// Rebuild a binary image from a medial axis by filling the disk of every
+/// point with the foreground color of `F`. All other pixels have the
+/// background color.
+pub fn reconstruct<F: ForegroundColor>(axis: &MedialAxis) -> image::DynamicImage {
+    let mut filled = vec![false; axis.width as usize * axis.height as usize];
+
+    for point in &axis.points {
+        // Radii from `medial_axis` are square roots of integers, so round to
+        // avoid reaching the background pixel exactly `radius` away
+        let squared_radius = if point.radius.is_finite() {
+            (point.radius * point.radius)
+                .round()
+                .min(u32::MAX as f32 - 1.0) as u32
+        } else {
+            u32::MAX
+        };
+        fill_disk(
+            &mut filled,
+            axis.width,
+            axis.height,
+            (point.x, point.y),
+            squared_radius,
+        );
+    }
+
+    let img = image::GrayImage::from_fn(axis.width, axis.height, |x, y| {
+        if filled[y as usize * axis.width as usize + x as usize] {
+            image::Luma([!F::BACKGROUND_COLOR])
+        } else {
+            image::Luma([F::BACKGROUND_COLOR])
+        }
+    });
+
+    image::DynamicImage::ImageLuma8(img)
+}
+
+/// Set every pixel which is closer than the square root of `squared_radius` to
+/// `center` in a `width` by `height` buffer. A `squared_radius` of `u32::MAX`
+/// is infinite and sets the whole buffer.
+fn fill_disk(
+    buffer: &mut [bool],
+    width: u32,
+    height: u32,
+    center: (u32, u32),
+    squared_radius: u32,
+) {
+    if squared_radius == 0 {
+        return;
+    } else if squared_radius == u32::MAX {
+        buffer.iter_mut().for_each(|b| *b = true);
+        return;
+    }
+
+    let limit = i64::from(squared_radius) - 1;
+    let (cx, cy) = (i64::from(center.0), i64::from(center.1));
+    let extent = integer_sqrt(limit as u64) as i64;
+
+    for y in (cy - extent).max(0)..=(cy + extent).min(i64::from(height) - 1) {
+        let half = integer_sqrt((limit - (y - cy).pow(2)) as u64) as i64;
+        let start = (cx - half).max(0) as usize;
+        let end = (cx + half).min(i64::from(width) - 1) as usize;
+        let row = y as usize * width as usize;
+        buffer[row + start..=row + end]
+            .iter_mut()
+            .for_each(|b| *b = true);
+    }
+}
+
+/// The largest squared distance from a pixel one step away, orthogonally and
+/// diagonally, to a pixel inside of a disk with a squared radius of
+/// `squared_radius`. The disk contains the pixels which are closer than the
+/// radius to its center.
+fn disk_reach(squared_radius: u32) -> [u32; 2] {
+    if squared_radius == u32::MAX {
+        return [u32::MAX; 2];
+    }
+
+    let limit = u64::from(squared_radius) - 1;
+    let mut reach = [0; 2];
+    let mut x = 0;
+    while x * x <= limit {
+        let y = integer_sqrt(limit - x * x);
+        reach[0] = reach[0].max((x + 1).pow(2) + y * y);
+        reach[1] = reach[1].max((x + 1).pow(2) + (y + 1).pow(2));
+        x += 1;
+    }
+
+    reach.map(|r| r.min(u64::from(u32::MAX - 1)) as u32)
+}
+
+/// The largest integer whose square is at most `n`.
+fn integer_sqrt(n: u64) -> u64 {
+    let mut root = (n as f64).sqrt() as u64;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+
+    root
+}
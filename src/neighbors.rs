@@ -1,6 +1,6 @@
 //! Struct and utilities for calculating the status of neighboring pixels.
 
-use crate::{Edge, ForegroundColor};
+use crate::{BinaryImage, Edge, ForegroundColor};
 
 /// Struct with information describing the surrounding pixels.
 pub struct NeighborInfo {
@@ -28,6 +28,27 @@ impl NeighborInfo {
 
         transitions
     }
+
+    /// Calculate and return the crossing number, the number of transitions from
+    /// background to foreground going around the neighbors, where pixels which
+    /// [don't exist](crate::Edge::DoesNotExist) are background.
+    ///
+    /// In a thinned image the crossing number is `1` at the end of a line, `2`
+    /// along a line, and the number of branches where lines meet.
+    pub fn crossing_number(&self) -> u8 {
+        let mut edge_status = self.edge_status;
+        for edge in &mut edge_status {
+            if *edge == Edge::DoesNotExist {
+                *edge = Edge::Empty;
+            }
+        }
+
+        NeighborInfo {
+            edge_status,
+            ..*self
+        }
+        .transitions()
+    }
 }
 
 /// Calculate and return a [`NeighborInfo`](crate::neighbors::NeighborInfo)
@@ -139,3 +160,114 @@ pub fn get_neighbor_info<F: ForegroundColor>(
         edge_status: [p2, p3, p4, p5, p6, p7, p8, p9],
     }
 }
+
+/// Calculate and return the status of the neighboring pixels packed into the
+/// bits of a `u8`. Bit `0` is set if the north neighbor `p2` is filled and the
+/// following bits continue clockwise to the north-west neighbor `p9`, matching
+/// the order of [`NeighborInfo::edge_status`](NeighborInfo::edge_status).
+///
+/// Returns `None` for pixels on the border of the image which don't have 8
+/// neighbors, and for coordinates outside of the image.
+///
+/// ```
+/// use skeletonize::foreground;
+/// use skeletonize::neighbors::get_neighbor_bits;
+///
+/// // Only the pixel north of the center is filled
+/// let img = image::GrayImage::from_fn(3, 3, |x, y| {
+///     image::Luma([if (x, y) == (1, 0) { 255 } else { 0 }])
+/// });
+///
+/// assert_eq!(get_neighbor_bits::<foreground::White>(&img, 1, 1), Some(1));
+/// assert_eq!(get_neighbor_bits::<foreground::White>(&img, 0, 1), None);
+/// assert_eq!(get_neighbor_bits::<foreground::White>(&img, u32::MAX, 1), None);
+/// ```
+pub fn get_neighbor_bits<F: ForegroundColor>(img: &image::GrayImage, x: u32, y: u32) -> Option<u8> {
+    let (width, height) = img.dimensions();
+    if x == 0 || y == 0 || x >= width.saturating_sub(1) || y >= height.saturating_sub(1) {
+        return None;
+    }
+
+    let width = width as usize;
+    let center = y as usize * width + x as usize;
+    let buf = img.as_raw();
+    let filled = |i: usize| u8::from(buf[i] != F::BACKGROUND_COLOR);
+
+    Some(
+        filled(center - width)
+            | filled(center - width + 1) << 1
+            | filled(center + 1) << 2
+            | filled(center + width + 1) << 3
+            | filled(center + width) << 4
+            | filled(center + width - 1) << 5
+            | filled(center - 1) << 6
+            | filled(center - width - 1) << 7,
+    )
+}
+
+/// Iterate over the coordinates of the pixels surrounding `(x, y)` which lie
+/// within the image.
+pub(crate) fn surrounding(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> impl Iterator<Item = (u32, u32)> {
+    let x_range = x.saturating_sub(1)..=x.saturating_add(1).min(width - 1);
+    let y_range = y.saturating_sub(1)..=y.saturating_add(1).min(height - 1);
+
+    y_range
+        .flat_map(move |ny| x_range.clone().map(move |nx| (nx, ny)))
+        .filter(move |&pos| pos != (x, y))
+}
+
+/// Offsets of the neighbors `p2` to `p9` of a pixel, clockwise from north.
+pub(crate) const RING_OFFSETS: [(i64, i64); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// Pack the neighbors of `(x, y)` into a `u8` in the same order as
+/// [`get_neighbor_bits`](get_neighbor_bits), where pixels outside of the image
+/// are background.
+pub(crate) fn neighbor_ring(img: &BinaryImage, x: u32, y: u32) -> u8 {
+    let (width, height) = img.dimensions();
+
+    RING_OFFSETS
+        .iter()
+        .enumerate()
+        .fold(0, |ring, (bit, &(dx, dy))| {
+            let (nx, ny) = (i64::from(x) + dx, i64::from(y) + dy);
+            let inside = nx >= 0 && ny >= 0 && nx < i64::from(width) && ny < i64::from(height);
+            if inside && img.get(nx as u32, ny as u32) {
+                ring | 1 << bit
+            } else {
+                ring
+            }
+        })
+}
+
+/// The number of transitions from background to foreground going around a
+/// neighbor ring, which is `1` for the end of a line, `2` for the middle of a
+/// line, and `3` or more where lines branch.
+pub(crate) fn crossing_number(ring: u8) -> u8 {
+    let mut edge_status = [Edge::Empty; 8];
+    for (bit, edge) in edge_status.iter_mut().enumerate() {
+        if ring >> bit & 1 == 1 {
+            *edge = Edge::Filled;
+        }
+    }
+
+    NeighborInfo {
+        filled: ring.count_ones() as u8,
+        neighbors: 8,
+        edge_status,
+    }
+    .transitions()
+}
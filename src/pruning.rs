@@ -0,0 +1,225 @@
+//! Removal of short spurs from skeletons.
+//!
+//! Noise along the boundary of a shape leaves short branches on the skeleton
+//! produced by [`thin_image_edges`](crate::thin_image_edges). Pruning removes
+//! these spurs while keeping the longer branches of the skeleton.
+//!
+//! Endpoints are skeleton pixels whose neighbors form a single group, where
+//! the crossing number of the pixel is `1`. Junctions are pixels with three or
+//! more groups of neighbors.
+
+use crate::error::{LumaConversionErrorKind, SkeletonizeError};
+use crate::neighbors::{crossing_number, neighbor_ring, surrounding};
+use crate::{BinaryImage, ForegroundColor};
+
+/// The method used to remove spurs from a skeleton.
+///
+/// ### Reference
+///
+/// <span id="erosion"></span>Gonzalez, Rafael C. & Woods, Richard E. (2018).
+/// Digital Image Processing, 4th Edition. Pearson. 673-675.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PruningMethod {
+    /// Remove branches with fewer than `length` pixels which run from an
+    /// endpoint to a junction, starting with the shortest branches. Branches
+    /// are removed one endpoint at a time, which never disconnects the rest of
+    /// the skeleton. Lines without a junction are never removed.
+    #[default]
+    Branches,
+    /// Remove every endpoint `length - 1` times, then regrow the branches
+    /// which remain from their new endpoints by up to `length - 1` pixels
+    /// within the original skeleton. Lines without a junction which are shorter
+    /// than `2 * length - 1` pixels are removed entirely.
+    ///
+    /// See [PruningMethod](crate::pruning::PruningMethod#erosion) for
+    /// reference.
+    Erosion,
+}
+
+/// Remove spurs shorter than `length` pixels from a thinned image using one of
+/// the methods in [`PruningMethod`](PruningMethod). Returns the number of
+/// pixels that were removed.
+///
+/// Pixels which aren't the background color of `F` are part of the skeleton,
+/// and removed pixels are set to the background color.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::pruning::{prune, PruningMethod};
+/// use skeletonize::foreground;
+///
+/// // A line with spurs of 3 and 4 pixels, a spur of exactly 5 pixels, and a
+/// // 10 pixel branch
+/// let luma = image::GrayImage::from_fn(32, 24, |x, y| {
+///     let line = y == 10 && (2..30).contains(&x);
+///     let short = (x == 15 && (7..10).contains(&y)) || (x == 12 && (11..15).contains(&y));
+///     let exact = x == 20 && (5..10).contains(&y);
+///     let branch = x == 8 && (11..21).contains(&y);
+///     image::Luma([if line || short || exact || branch { 255 } else { 0 }])
+/// });
+///
+/// for method in [PruningMethod::Branches, PruningMethod::Erosion] {
+///     let mut img = image::DynamicImage::ImageLuma8(luma.clone());
+///     assert_eq!(prune::<foreground::White>(&mut img, method, 5)?, 7);
+///
+///     let pruned = img.as_luma8().unwrap();
+///     assert_eq!(pruned[(15, 8)][0], 0);
+///     assert_eq!(pruned[(12, 14)][0], 0);
+///     assert_eq!(pruned[(20, 5)][0], 255);
+///     assert_eq!(pruned[(15, 10)][0], 255);
+///     assert_eq!(pruned[(8, 20)][0], 255);
+///     assert_eq!(pruned[(2, 10)][0], 255);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn prune<F: ForegroundColor>(
+    img: &mut image::DynamicImage,
+    method: PruningMethod,
+    length: u32,
+) -> Result<u64, SkeletonizeError> {
+    let luma = img.as_mut_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::PruningMutableLuma,
+    ))?;
+
+    let mut binary = BinaryImage::from_luma::<F>(luma);
+    let removed = prune_binary_image(&mut binary, method, length);
+
+    for (x, y, p) in luma.enumerate_pixels_mut() {
+        if p[0] != F::BACKGROUND_COLOR && !binary.get(x, y) {
+            p[0] = F::BACKGROUND_COLOR;
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Remove spurs shorter than `length` pixels from a thinned
+/// [`BinaryImage`](crate::BinaryImage) using one of the methods in
+/// [`PruningMethod`](PruningMethod). Returns the number of pixels that were
+/// removed.
+pub fn prune_binary_image(img: &mut BinaryImage, method: PruningMethod, length: u32) -> u64 {
+    let before = img.count_foreground();
+
+    match method {
+        PruningMethod::Branches => prune_branches(img, length),
+        PruningMethod::Erosion => prune_erosion(img, length),
+    }
+
+    before - img.count_foreground()
+}
+
+/// Repeatedly remove the shortest spurs until none are shorter than `length`.
+fn prune_branches(img: &mut BinaryImage, length: u32) {
+    let max_pixels = length.saturating_sub(1);
+
+    loop {
+        let mut spurs: Vec<(usize, (u32, u32))> = endpoints(img)
+            .into_iter()
+            .filter_map(|start| spur(img, start, max_pixels).map(|pixels| (pixels.len(), start)))
+            .collect();
+        if spurs.is_empty() {
+            break;
+        }
+        spurs.sort_unstable();
+
+        // Removing one spur can join two others into a line, so check each
+        // spur again before removing it
+        let mut removed_any = false;
+        for (_, (x, y)) in spurs {
+            if !img.get(x, y) || crossing_number(neighbor_ring(img, x, y)) != 1 {
+                continue;
+            }
+            if let Some(pixels) = spur(img, (x, y), max_pixels) {
+                pixels
+                    .into_iter()
+                    .for_each(|(px, py)| img.set(px, py, false));
+                removed_any = true;
+            }
+        }
+
+        if !removed_any {
+            break;
+        }
+    }
+}
+
+/// Erode endpoints `length - 1` times and regrow from the remaining endpoints.
+fn prune_erosion(img: &mut BinaryImage, length: u32) {
+    let original = img.clone();
+    let (width, height) = img.dimensions();
+    let passes = length.saturating_sub(1);
+
+    for _ in 0..passes {
+        let ends = endpoints(img);
+        if ends.is_empty() {
+            break;
+        }
+        ends.into_iter().for_each(|(x, y)| img.set(x, y, false));
+    }
+
+    // Grow back along the original skeleton from the endpoints which remain
+    let mut frontier = endpoints(img);
+    for _ in 0..passes {
+        let mut grown = Vec::new();
+        for (x, y) in frontier {
+            for (nx, ny) in surrounding(x, y, width, height) {
+                if original.get(nx, ny) && !img.get(nx, ny) {
+                    img.set(nx, ny, true);
+                    grown.push((nx, ny));
+                }
+            }
+        }
+        frontier = grown;
+    }
+}
+
+/// Find the pixels of a skeleton which have a crossing number of `1`.
+fn endpoints(img: &BinaryImage) -> Vec<(u32, u32)> {
+    let (width, height) = img.dimensions();
+
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| img.get(x, y) && crossing_number(neighbor_ring(img, x, y)) == 1)
+        .collect()
+}
+
+/// Follow the branch which ends at the endpoint `start` by removing one
+/// endpoint at a time. Returns the pixels of the branch if it reaches a
+/// junction within `max_pixels` pixels. The image is left unchanged.
+fn spur(img: &mut BinaryImage, start: (u32, u32), max_pixels: u32) -> Option<Vec<(u32, u32)>> {
+    let (width, height) = img.dimensions();
+    let mut pixels = Vec::new();
+    let mut current = Some(start);
+    let mut reached_junction = false;
+
+    while let Some((x, y)) = current.take() {
+        if pixels.len() >= max_pixels as usize {
+            break;
+        }
+        img.set(x, y, false);
+        pixels.push((x, y));
+
+        // The branch continues to the neighbor which became an endpoint, and
+        // ends at a neighbor which still joins other branches. A neighbor
+        // without any other neighbors is the last pixel of a line.
+        let crossings: Vec<((u32, u32), u8)> = surrounding(x, y, width, height)
+            .filter(|&(nx, ny)| img.get(nx, ny))
+            .map(|(nx, ny)| ((nx, ny), crossing_number(neighbor_ring(img, nx, ny))))
+            .collect();
+
+        current = crossings
+            .iter()
+            .find(|&&(_, c)| c == 1)
+            .map(|&(pos, _)| pos);
+        reached_junction = current.is_none() && crossings.iter().any(|&(_, c)| c >= 2);
+    }
+
+    pixels.iter().for_each(|&(x, y)| img.set(x, y, true));
+
+    if reached_junction {
+        Some(pixels)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,173 @@
+//! Writing traced skeletons as SVG vector images.
+//!
+//! Polylines from [`vectorize`](crate::vectorize) are written as `<polyline>`
+//! elements, or as `<path>` elements of cubic Bézier curves, inside a `viewBox`
+//! matching the size of the source image so that the lines cover the pixels
+//! they were traced from.
+
+use crate::vectorize::Polyline;
+
+/// Styling options for [`write_svg`](write_svg) and [`to_svg`](to_svg).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    /// Width of the lines in pixels of the source image.
+    pub stroke_width: f32,
+    /// Color of the lines, which can be any SVG color such as `black` or
+    /// `#ff8000`.
+    pub stroke: String,
+    /// Draw smooth cubic Bézier curves passing through the points of each
+    /// polyline instead of straight segments. The curves follow Catmull-Rom
+    /// splines, so polylines should be
+    /// [simplified](crate::vectorize::Simplification) first to avoid curving
+    /// through every pixel.
+    pub curves: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            stroke_width: 1.0,
+            stroke: String::from("black"),
+            curves: false,
+        }
+    }
+}
+
+/// Write polylines as an SVG image to `writer`, with a `viewBox` of `width` by
+/// `height` pixels.
+///
+/// Lines have round caps and joins, so polylines with a single point are drawn
+/// as dots.
+pub fn write_svg<W: std::io::Write>(
+    mut writer: W,
+    lines: &[Polyline],
+    width: u32,
+    height: u32,
+    options: &SvgOptions,
+) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    )?;
+    writeln!(
+        writer,
+        r#"<g fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round">"#,
+        escape(&options.stroke),
+        number(options.stroke_width)
+    )?;
+
+    for line in lines.iter().filter(|line| !line.is_empty()) {
+        if line.len() == 1 {
+            let (x, y) = line[0];
+            writeln!(writer, r#"<path d="M{} {}h0"/>"#, number(x), number(y))?;
+        } else if options.curves {
+            writeln!(writer, r#"<path d="{}"/>"#, curve_path(line))?;
+        } else {
+            let points: Vec<String> = line
+                .iter()
+                .map(|&(x, y)| format!("{},{}", number(x), number(y)))
+                .collect();
+            writeln!(writer, r#"<polyline points="{}"/>"#, points.join(" "))?;
+        }
+    }
+
+    writeln!(writer, "</g>")?;
+    writeln!(writer, "</svg>")
+}
+
+/// Create an SVG image of polylines as a `String`, with a `viewBox` of `width`
+/// by `height` pixels. See [`write_svg`](write_svg) for details.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::foreground;
+/// use skeletonize::svg::{to_svg, SvgOptions};
+/// use skeletonize::vectorize::{polylines, Simplification};
+///
+/// // A diagonal line
+/// let luma = image::GrayImage::from_fn(10, 10, |x, y| {
+///     image::Luma([if x == y && (2..8).contains(&x) { 255 } else { 0 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let lines = polylines::<foreground::White>(&img, Simplification::DouglasPeucker, 0.5)?;
+/// let options = SvgOptions {
+///     stroke_width: 2.0,
+///     ..SvgOptions::default()
+/// };
+/// let svg = to_svg(&lines, 10, 10, &options);
+///
+/// assert!(svg.contains(r#"viewBox="0 0 10 10""#));
+/// assert!(svg.contains(r#"stroke-width="2""#));
+/// assert!(svg.contains(r#"<polyline points="2.5,2.5 7.5,7.5"/>"#));
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_svg(lines: &[Polyline], width: u32, height: u32, options: &SvgOptions) -> String {
+    let mut buffer = Vec::new();
+    write_svg(&mut buffer, lines, width, height, options).expect("writing to a `Vec` doesn't fail");
+
+    String::from_utf8(buffer).expect("SVG output is UTF-8")
+}
+
+/// Path data for a Catmull-Rom spline through the points of `line`, as cubic
+/// Bézier curves. Closed loops continue smoothly through their first point.
+fn curve_path(line: &[(f32, f32)]) -> String {
+    let last = line.len() - 1;
+    let closed = last > 1 && line[0] == line[last];
+
+    // Neighbors past the ends of an open line repeat the end points
+    let point = |i: isize| -> (f32, f32) {
+        if closed {
+            line[i.rem_euclid(last as isize) as usize]
+        } else {
+            line[i.clamp(0, last as isize) as usize]
+        }
+    };
+
+    let mut path = format!("M{} {}", number(line[0].0), number(line[0].1));
+    for i in 0..last as isize {
+        let (before, start, end, after) = (point(i - 1), point(i), point(i + 1), point(i + 2));
+        let control1 = (
+            start.0 + (end.0 - before.0) / 6.0,
+            start.1 + (end.1 - before.1) / 6.0,
+        );
+        let control2 = (
+            end.0 - (after.0 - start.0) / 6.0,
+            end.1 - (after.1 - start.1) / 6.0,
+        );
+        path.push_str(&format!(
+            "C{} {} {} {} {} {}",
+            number(control1.0),
+            number(control1.1),
+            number(control2.0),
+            number(control2.1),
+            number(end.0),
+            number(end.1)
+        ));
+    }
+    if closed {
+        path.push('Z');
+    }
+
+    path
+}
+
+/// Format a coordinate with at most two decimal places.
+fn number(value: f32) -> String {
+    let text = format!("{value:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+
+    match text {
+        "-0" => String::from("0"),
+        _ => String::from(text),
+    }
+}
+
+/// Escape the characters which can't appear in an attribute value.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
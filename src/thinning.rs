@@ -1,149 +1,315 @@
 //! Functions for performing image thinning.
 
-use image::GenericImage;
+use core::marker::PhantomData;
 
 use crate::error::{LumaConversionErrorKind, SkeletonizeError};
-use crate::neighbors::get_neighbor_info;
-use crate::{Edge, ForegroundColor, MarkingMethod};
+use crate::lookup::deletion_table;
+use crate::neighbors::{get_neighbor_bits, surrounding};
+use crate::{BinaryImage, ForegroundColor, MarkingMethod};
 
 /// Perform image thinning on a binarized image `img` using one of the methods
 /// in [`MarkingMethod`](crate::MarkingMethod). Returns the number of iterations
 /// needed for thinning on successful completion.
 ///
 /// `iterations` is an optional parameter set to `u32::MAX` if `None`.
-#[allow(clippy::collapsible_else_if)]
-#[allow(clippy::nonminimal_bool)]
 pub fn thin_image_edges<F: ForegroundColor>(
     img: &mut image::DynamicImage,
     method: MarkingMethod,
     iterations: Option<u32>,
 ) -> Result<u32, SkeletonizeError> {
-    let mut pixels_to_remove = Vec::new();
-    let mut phase_one = true;
-    let iterations = iterations.unwrap_or(u32::MAX);
+    let luma_img = img.as_mut_luma8().ok_or(SkeletonizeError::LumaConversion(
+        LumaConversionErrorKind::ImageThinningLuma,
+    ))?;
 
-    for iters in 0..iterations {
-        let luma_img = img.as_luma8().ok_or(SkeletonizeError::LumaConversion(
-            LumaConversionErrorKind::ImageThinningLuma,
-        ))?;
-        let (width, height) = luma_img.dimensions();
-
-        // Mark pixels to remove
-        for (x, y, p) in luma_img.enumerate_pixels() {
-            if *p == image::Luma([F::BACKGROUND_COLOR]) {
-                continue;
-            }
+    thin(
+        &mut LumaImage::<F> {
+            img: luma_img,
+            foreground: PhantomData,
+        },
+        method,
+        iterations,
+    )
+}
 
-            let info = get_neighbor_info::<F>(luma_img, width, height, x, y);
-            let [p2, p3, p4, p5, p6, p7, p8, p9] = info.edge_status;
+/// Perform image thinning on a [`BinaryImage`](crate::BinaryImage) using one of
+/// the methods in [`MarkingMethod`](crate::MarkingMethod). Returns the number
+/// of iterations needed for thinning on successful completion.
+///
+/// The result is the same as [`thin_image_edges`](crate::thin_image_edges) on
+/// the equivalent grayscale image.
+///
+/// `iterations` is an optional parameter set to `u32::MAX` if `None`.
+pub fn thin_binary_image_edges(
+    img: &mut BinaryImage,
+    method: MarkingMethod,
+    iterations: Option<u32>,
+) -> Result<u32, SkeletonizeError> {
+    thin(img, method, iterations)
+}
 
-            match method {
-                MarkingMethod::Standard => {
-                    // Zhang and Suen, 1984
+/// Binary image storage which can be thinned.
+trait Thinnable: Sync {
+    fn dimensions(&self) -> (u32, u32);
+    fn is_foreground(&self, x: u32, y: u32) -> bool;
+    fn neighbor_bits(&self, x: u32, y: u32) -> Option<u8>;
+    fn remove(&mut self, x: u32, y: u32);
+}
 
-                    // Don't mark if we don't have 8 neighbors and 2..=6 aren't filled
-                    if !(2..=6).contains(&info.filled) || info.neighbors != 8 {
-                        continue;
-                    }
+/// A grayscale image with the foreground color `F`.
+struct LumaImage<'a, F> {
+    img: &'a mut image::GrayImage,
+    foreground: PhantomData<fn() -> F>,
+}
 
-                    // Count the number of times an edge transitions from empty to filled
-                    let mut transitions = 0;
-                    for pair in [p2, p3, p4, p5, p6, p7, p8, p9, p2].windows(2) {
-                        if pair[0] == Edge::Empty && pair[1] == Edge::Filled {
-                            transitions += 1;
-                        }
-                    }
+impl<F: ForegroundColor> Thinnable for LumaImage<'_, F> {
+    fn dimensions(&self) -> (u32, u32) {
+        self.img.dimensions()
+    }
 
-                    if transitions != 1 {
-                        continue;
-                    }
+    fn is_foreground(&self, x: u32, y: u32) -> bool {
+        self.img.get_pixel(x, y)[0] != F::BACKGROUND_COLOR
+    }
 
-                    if phase_one {
-                        if (p2 == Edge::Empty || p4 == Edge::Empty || p6 == Edge::Empty)
-                            && (p4 == Edge::Empty || p6 == Edge::Empty || p8 == Edge::Empty)
-                        {
-                            pixels_to_remove.push((x, y));
-                        }
-                    } else {
-                        if (p2 == Edge::Empty || p4 == Edge::Empty || p8 == Edge::Empty)
-                            && (p2 == Edge::Empty || p6 == Edge::Empty || p8 == Edge::Empty)
-                        {
-                            pixels_to_remove.push((x, y));
-                        }
-                    }
-                }
-                MarkingMethod::Modified => {
-                    // Chen and Hsu, 1988
+    fn neighbor_bits(&self, x: u32, y: u32) -> Option<u8> {
+        get_neighbor_bits::<F>(self.img, x, y)
+    }
 
-                    if !(2..=7).contains(&info.filled) || info.neighbors != 8 {
-                        continue;
-                    }
+    fn remove(&mut self, x: u32, y: u32) {
+        self.img.put_pixel(x, y, image::Luma([F::BACKGROUND_COLOR]));
+    }
+}
 
-                    let mut transitions = 0;
-                    for pair in [p2, p3, p4, p5, p6, p7, p8, p9, p2].windows(2) {
-                        if pair[0] == Edge::Empty && pair[1] == Edge::Filled {
-                            transitions += 1;
-                        }
-                    }
+impl Thinnable for BinaryImage {
+    fn dimensions(&self) -> (u32, u32) {
+        self.dimensions()
+    }
 
-                    if !(transitions == 1 || transitions == 2) {
-                        continue;
-                    }
+    fn is_foreground(&self, x: u32, y: u32) -> bool {
+        self.get(x, y)
+    }
 
-                    if phase_one {
-                        if transitions == 1 {
-                            if (p2 == Edge::Empty || p4 == Edge::Empty || p6 == Edge::Empty)
-                                && (p4 == Edge::Empty || p6 == Edge::Empty || p8 == Edge::Empty)
-                            {
-                                pixels_to_remove.push((x, y));
-                            }
-                        } else {
-                            if ((p2 == Edge::Filled && p4 == Edge::Filled)
-                                && (p6 == Edge::Empty && p7 == Edge::Empty && p8 == Edge::Empty))
-                                || ((p4 == Edge::Filled && p6 == Edge::Filled)
-                                    && (p2 == Edge::Empty
-                                        && p8 == Edge::Empty
-                                        && p9 == Edge::Empty))
-                            {
-                                pixels_to_remove.push((x, y));
-                            }
-                        }
-                    } else {
-                        if transitions == 1 {
-                            if (p2 == Edge::Empty || p4 == Edge::Empty || p8 == Edge::Empty)
-                                && (p2 == Edge::Empty || p6 == Edge::Empty || p8 == Edge::Empty)
-                            {
-                                pixels_to_remove.push((x, y));
-                            }
-                        } else {
-                            if ((p2 == Edge::Filled && p8 == Edge::Filled)
-                                && (p4 == Edge::Empty && p5 == Edge::Empty && p6 == Edge::Empty))
-                                || ((p6 == Edge::Filled && p8 == Edge::Filled)
-                                    && (p2 == Edge::Empty
-                                        && p3 == Edge::Empty
-                                        && p4 == Edge::Empty))
-                            {
-                                pixels_to_remove.push((x, y));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    fn neighbor_bits(&self, x: u32, y: u32) -> Option<u8> {
+        self.neighbor_bits(x, y)
+    }
+
+    fn remove(&mut self, x: u32, y: u32) {
+        self.set(x, y, false);
+    }
+}
+
+fn thin<T: Thinnable>(
+    img: &mut T,
+    method: MarkingMethod,
+    iterations: Option<u32>,
+) -> Result<u32, SkeletonizeError> {
+    #[cfg(not(feature = "rayon"))]
+    let mark = mark_pixels;
+    #[cfg(feature = "rayon")]
+    let mark = par_mark_pixels;
+
+    thin_with(img, method, iterations, mark)
+}
+
+/// Marks the pixels from a list of candidates to remove, given the deletion
+/// table of the pass.
+type MarkPixels<T> = fn(&T, &[(u32, u32)], &[bool; 256], &mut Vec<(u32, u32)>);
+
+/// Thin `img`, using `mark` to find the pixels to remove in each pass.
+fn thin_with<T: Thinnable>(
+    img: &mut T,
+    method: MarkingMethod,
+    iterations: Option<u32>,
+    mark: MarkPixels<T>,
+) -> Result<u32, SkeletonizeError> {
+    let (width, height) = img.dimensions();
+    let mut pixels_to_remove = Vec::new();
+    let mut phase_one = true;
+    let iterations = iterations.unwrap_or(u32::MAX);
+
+    // Only foreground pixels with a background neighbor can be removed
+    let mut candidates: Vec<_> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| {
+            img.is_foreground(x, y)
+                && matches!(img.neighbor_bits(x, y), Some(bits) if bits != u8::MAX)
+        })
+        .collect();
+    let mut previous_neighbors = candidates.clone();
+    let mut neighbors = Vec::new();
+    // One bit per pixel, so the working set stays as small as a `BinaryImage`
+    let mut queued = BinaryImage::new(width, height);
+
+    for iters in 0..iterations {
+        let table = deletion_table(method, phase_one);
+
+        mark(img, &candidates, table, &mut pixels_to_remove);
 
         phase_one = !phase_one;
 
         // Replace marked pixels with background color to thin the edges
         for &(x, y) in &pixels_to_remove {
-            img.put_pixel(x, y, image::Rgba([F::BACKGROUND_COLOR; 4]));
+            img.remove(x, y);
         }
 
         if pixels_to_remove.is_empty() {
             return Ok(iters);
         }
 
+        // A pixel can only become removable after its neighborhood changes or
+        // during the other subiteration, so the next pass examines the
+        // neighbors of pixels removed in the last two passes.
+        neighbors.clear();
+        for &(x, y) in &pixels_to_remove {
+            for (nx, ny) in surrounding(x, y, width, height) {
+                if !queued.get(nx, ny) && img.is_foreground(nx, ny) {
+                    queued.set(nx, ny, true);
+                    neighbors.push((nx, ny));
+                }
+            }
+        }
+
+        candidates.clear();
+        candidates.extend_from_slice(&neighbors);
+        for &(x, y) in &previous_neighbors {
+            if !queued.get(x, y) {
+                queued.set(x, y, true);
+                candidates.push((x, y));
+            }
+        }
+        for &(x, y) in &candidates {
+            queued.set(x, y, false);
+        }
+
+        core::mem::swap(&mut previous_neighbors, &mut neighbors);
         pixels_to_remove.clear();
     }
 
     Err(SkeletonizeError::MaxThinningIterations)
 }
+
+/// Mark the pixels from `candidates` to remove in `pixels_to_remove`.
+#[cfg(any(test, not(feature = "rayon")))]
+fn mark_pixels<T: Thinnable>(
+    img: &T,
+    candidates: &[(u32, u32)],
+    table: &[bool; 256],
+    pixels_to_remove: &mut Vec<(u32, u32)>,
+) {
+    pixels_to_remove.extend(
+        candidates
+            .iter()
+            .copied()
+            .filter(|&(x, y)| is_marked(img, x, y, table)),
+    );
+}
+
+/// Mark the pixels from `candidates` to remove in `pixels_to_remove`.
+///
+/// The candidates are split into bands which are marked in parallel, then the
+/// marked pixels of each band are appended in order.
+#[cfg(feature = "rayon")]
+fn par_mark_pixels<T: Thinnable>(
+    img: &T,
+    candidates: &[(u32, u32)],
+    table: &[bool; 256],
+    pixels_to_remove: &mut Vec<(u32, u32)>,
+) {
+    use rayon::prelude::*;
+
+    /// Number of candidate pixels examined by each task.
+    const BAND_LEN: usize = 4096;
+
+    let bands: Vec<Vec<_>> = candidates
+        .par_chunks(BAND_LEN)
+        .map(|band| {
+            band.iter()
+                .copied()
+                .filter(|&(x, y)| is_marked(img, x, y, table))
+                .collect()
+        })
+        .collect();
+
+    for band in bands {
+        pixels_to_remove.extend(band);
+    }
+}
+
+/// Whether the pixel at `(x, y)` is a foreground pixel marked for removal by
+/// `table`.
+fn is_marked<T: Thinnable>(img: &T, x: u32, y: u32, table: &[bool; 256]) -> bool {
+    if !img.is_foreground(x, y) {
+        return false;
+    }
+
+    // Pixels without 8 neighbors are never removed
+    matches!(img.neighbor_bits(x, y), Some(bits) if table[usize::from(bits)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foreground::Black;
+
+    /// Blobs of random sizes, with far more edge pixels than a single band of
+    /// candidates marked in parallel.
+    fn blobs(width: u32, height: u32) -> image::GrayImage {
+        let mut state = 0x2545_f491_u32;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+
+        let mut img = image::GrayImage::from_pixel(width, height, image::Luma([255]));
+        for _ in 0..300 {
+            let (cx, cy) = (next() % width, next() % height);
+            let radius = 2 + next() % 12;
+            for y in cy.saturating_sub(radius)..(cy + radius).min(height) {
+                for x in cx.saturating_sub(radius)..(cx + radius).min(width) {
+                    let (dx, dy) = (x as i64 - cx as i64, y as i64 - cy as i64);
+                    if dx * dx + dy * dy <= i64::from(radius * radius) {
+                        img.put_pixel(x, y, image::Luma([0]));
+                    }
+                }
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn parallel_marking_matches_serial() {
+        let methods = [
+            MarkingMethod::Standard,
+            MarkingMethod::Modified,
+            MarkingMethod::GuoHall,
+        ];
+        let original = blobs(400, 300);
+
+        for method in methods {
+            let mut serial = original.clone();
+            let serial_iters = thin_with(
+                &mut LumaImage::<Black> {
+                    img: &mut serial,
+                    foreground: PhantomData,
+                },
+                method,
+                None,
+                mark_pixels,
+            )
+            .unwrap();
+
+            let mut img = image::DynamicImage::ImageLuma8(original.clone());
+            let iters = thin_image_edges::<Black>(&mut img, method, None).unwrap();
+            assert_eq!(iters, serial_iters);
+            assert_eq!(img.as_luma8().unwrap(), &serial);
+
+            let mut binary = BinaryImage::from_luma::<Black>(&original);
+            assert_eq!(
+                thin_binary_image_edges(&mut binary, method, None).unwrap(),
+                iters
+            );
+            assert_eq!(binary.to_luma::<Black>(), serial);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Tracing of thinned images into polylines.
+//!
+//! Each branch of a [`SkeletonGraph`](crate::graph::SkeletonGraph) becomes an
+//! ordered list of points, split wherever branches meet. The polylines can be
+//! simplified to fewer points and written to any vector format.
+//!
+//! Points are at the centers of pixels, so the pixel `(x, y)` becomes the point
+//! `(x + 0.5, y + 0.5)`.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::error::SkeletonizeError;
+use crate::graph::{skeleton_graph, NodeKind, SkeletonGraph};
+use crate::ForegroundColor;
+
+/// An ordered list of points. Closed loops repeat their first point at the
+/// end.
+pub type Polyline = Vec<(f32, f32)>;
+
+/// The method used to reduce the number of points in a polyline.
+///
+/// ### References
+///
+/// <span id="douglas-peucker"></span>Douglas, David H. & Peucker, Thomas K.
+/// (1973). Algorithms for the Reduction of the Number of Points Required to
+/// Represent a Digitized Line or its Caricature. Cartographica, 10(2), 112-122.
+///
+/// <span id="visvalingam"></span>Visvalingam, M. & Whyatt, J. D. (1993). Line
+/// Generalisation by Repeated Elimination of Points. The Cartographic Journal,
+/// 30(1), 46-51.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Simplification {
+    /// Keep every pixel of the skeleton.
+    #[default]
+    None,
+    /// Ramer-Douglas-Peucker simplification, which keeps the points needed for
+    /// the polyline to stay within `tolerance` pixels of every removed point.
+    ///
+    /// See [Simplification](crate::vectorize::Simplification#douglas-peucker)
+    /// for reference.
+    DouglasPeucker,
+    /// Visvalingam-Whyatt simplification, which repeatedly removes the point
+    /// forming the triangle with the smallest area with its neighbors, until
+    /// every triangle has an area of at least `tolerance` square pixels.
+    ///
+    /// See [Simplification](crate::vectorize::Simplification#visvalingam) for
+    /// reference.
+    Visvalingam,
+}
+
+/// Trace a thinned image into polylines, where every pixel which isn't the
+/// background color of `F` is part of the skeleton. The polylines are
+/// simplified with `method` using `tolerance`, which is ignored for
+/// [`Simplification::None`](Simplification::None).
+///
+/// Every branch of the skeleton becomes a polyline running between endpoints
+/// and junctions, and lines which meet at a junction share its point. Closed
+/// loops without a junction start and end at the same point, and pixels
+/// without neighbors become polylines with a single point.
+///
+/// ```
+/// # fn main() -> Result<(), skeletonize::error::SkeletonizeError> {
+/// use skeletonize::foreground;
+/// use skeletonize::vectorize::{polylines, Simplification};
+///
+/// // An `L` with a 10 pixel tall stem and a 6 pixel wide foot
+/// let luma = image::GrayImage::from_fn(12, 14, |x, y| {
+///     let stem = x == 2 && (2..12).contains(&y);
+///     let foot = y == 11 && (2..8).contains(&x);
+///     image::Luma([if stem || foot { 255 } else { 0 }])
+/// });
+/// let img = image::DynamicImage::ImageLuma8(luma);
+///
+/// let lines = polylines::<foreground::White>(&img, Simplification::None, 0.0)?;
+/// assert_eq!(lines.len(), 1);
+/// assert_eq!(lines[0].len(), 15);
+///
+/// // Only the ends and the corner are needed to draw the `L`
+/// let lines = polylines::<foreground::White>(&img, Simplification::DouglasPeucker, 0.5)?;
+/// assert_eq!(lines, [vec![(2.5, 2.5), (2.5, 11.5), (7.5, 11.5)]]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn polylines<F: ForegroundColor>(
+    img: &image::DynamicImage,
+    method: Simplification,
+    tolerance: f32,
+) -> Result<Vec<Polyline>, SkeletonizeError> {
+    let graph = skeleton_graph::<F>(img)?;
+
+    Ok(graph_polylines(&graph, method, tolerance))
+}
+
+/// Convert the branches of a [`SkeletonGraph`](crate::graph::SkeletonGraph)
+/// into polylines, simplified with `method` using `tolerance`. See
+/// [`polylines`](polylines) for details.
+pub fn graph_polylines(
+    graph: &SkeletonGraph,
+    method: Simplification,
+    tolerance: f32,
+) -> Vec<Polyline> {
+    let center = |(x, y): (u32, u32)| (x as f32 + 0.5, y as f32 + 0.5);
+
+    let branches = graph.branches().iter().map(|branch| {
+        let mut line = Vec::with_capacity(branch.pixels.len() + 2);
+
+        // Extend branches which touch a junction with several pixels to the
+        // center of the junction, so that they meet at the same point
+        let start = &graph.nodes()[branch.start];
+        if (start.x, start.y) != branch.pixels[0] {
+            line.push(center((start.x, start.y)));
+        }
+        line.extend(branch.pixels.iter().copied().map(center));
+        let end = &graph.nodes()[branch.end];
+        if Some(&(end.x, end.y)) != branch.pixels.last() {
+            line.push(center((end.x, end.y)));
+        }
+
+        simplify(&line, method, tolerance)
+    });
+
+    let isolated = graph
+        .nodes()
+        .iter()
+        .filter(|node| node.kind == NodeKind::Isolated)
+        .map(|node| vec![center((node.x, node.y))]);
+
+    branches.chain(isolated).collect()
+}
+
+/// Reduce the number of points in a polyline with `method` using `tolerance`.
+/// The first and last points are always kept.
+pub fn simplify(line: &[(f32, f32)], method: Simplification, tolerance: f32) -> Polyline {
+    match method {
+        Simplification::None => line.to_vec(),
+        Simplification::DouglasPeucker => douglas_peucker(line, tolerance),
+        Simplification::Visvalingam => visvalingam(line, tolerance),
+    }
+}
+
+/// Keep the farthest point from each segment while it's farther than
+/// `tolerance`, then continue with both halves.
+fn douglas_peucker(line: &[(f32, f32)], tolerance: f32) -> Polyline {
+    let last = match line.len() {
+        0..=2 => return line.to_vec(),
+        len => len - 1,
+    };
+
+    let mut keep = vec![false; line.len()];
+    keep[0] = true;
+    keep[last] = true;
+
+    // A closed loop has no segment to measure from, so split it at the point
+    // farthest from its start first
+    let mut segments = vec![(0, last)];
+    if line[0] == line[last] {
+        let (far, _) = farthest(line, 0, last);
+        keep[far] = true;
+        segments = vec![(0, far), (far, last)];
+    }
+
+    while let Some((start, end)) = segments.pop() {
+        if end - start < 2 {
+            continue;
+        }
+
+        let (far, distance) = farthest(line, start, end);
+        if distance > tolerance {
+            keep[far] = true;
+            segments.push((start, far));
+            segments.push((far, end));
+        }
+    }
+
+    line.iter()
+        .zip(keep)
+        .filter(|&(_, kept)| kept)
+        .map(|(&point, _)| point)
+        .collect()
+}
+
+/// The index of the point between `start` and `end` which is farthest from the
+/// segment joining them, and its distance.
+fn farthest(line: &[(f32, f32)], start: usize, end: usize) -> (usize, f32) {
+    (start + 1..end)
+        .map(|i| (i, segment_distance(line[i], line[start], line[end])))
+        .fold((start, f32::NEG_INFINITY), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+/// The distance from `point` to the closest point of the segment from `a` to
+/// `b`.
+fn segment_distance(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let squared_length = dx * dx + dy * dy;
+    let t = if squared_length > 0.0 {
+        (((point.0 - a.0) * dx + (point.1 - a.1) * dy) / squared_length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    (point.0 - a.0 - t * dx).hypot(point.1 - a.1 - t * dy)
+}
+
+/// Remove the point with the smallest triangle area until every area is at
+/// least `tolerance`.
+fn visvalingam(line: &[(f32, f32)], tolerance: f32) -> Polyline {
+    let len = line.len();
+    if len < 3 {
+        return line.to_vec();
+    }
+
+    let mut previous: Vec<usize> = (0..len).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (1..=len).collect();
+    let mut removed = vec![false; len];
+
+    // Removing a point whose neighbors are the same point would collapse a
+    // closed loop, so those points aren't candidates
+    let area = |i: usize, previous: &[usize], next: &[usize]| {
+        let (a, b, c) = (line[previous[i]], line[i], line[next[i]]);
+        if a == c {
+            None
+        } else {
+            Some(((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() / 2.0)
+        }
+    };
+
+    // Areas aren't negative, so their bits sort in the same order as the areas
+    let mut areas: Vec<Option<f32>> = (0..len)
+        .map(|i| match i {
+            0 => None,
+            i if i == len - 1 => None,
+            i => area(i, &previous, &next),
+        })
+        .collect();
+    let mut heap: BinaryHeap<_> = areas
+        .iter()
+        .enumerate()
+        .filter_map(|(i, a)| a.map(|a| Reverse((a.to_bits(), i))))
+        .collect();
+
+    while let Some(Reverse((bits, i))) = heap.pop() {
+        if removed[i] || areas[i].map(f32::to_bits) != Some(bits) {
+            continue;
+        }
+        if f32::from_bits(bits) >= tolerance {
+            break;
+        }
+
+        removed[i] = true;
+        let (before, after) = (previous[i], next[i]);
+        next[before] = after;
+        previous[after] = before;
+
+        for neighbor in [before, after] {
+            if neighbor != 0 && neighbor != len - 1 {
+                areas[neighbor] = area(neighbor, &previous, &next);
+                if let Some(a) = areas[neighbor] {
+                    heap.push(Reverse((a.to_bits(), neighbor)));
+                }
+            }
+        }
+    }
+
+    line.iter()
+        .zip(removed)
+        .filter(|&(_, removed)| !removed)
+        .map(|(&point, _)| point)
+        .collect()
+}